@@ -34,7 +34,8 @@ typedef struct {
 void *worker_fast(void *arg) {
     ThreadArgs *args = (ThreadArgs *)arg;
     int steps = args->steps;
-    double factor = ALPHA * DT / (DX * DX);
+    double factor_x = ALPHA * DT / (DX * DX);
+    double factor_y = ALPHA * DT / (DY * DY);
     
     // ローカルポインタとして保持
     double *src = args->grid_a_base;
@@ -57,10 +58,9 @@ void *worker_fast(void *arg) {
         for (int i = r_start; i < r_end; i++) {
             for (int j = 1; j < M - 1; j++) {
                 int idx = i * M + j;
-                double laplacian = src[(i + 1) * M + j] + src[(i - 1) * M + j] +
-                                   src[i * M + (j + 1)] + src[i * M + (j - 1)] -
-                                   4.0 * src[idx];
-                dst[idx] = src[idx] + factor * laplacian;
+                double lap_x = src[(i + 1) * M + j] + src[(i - 1) * M + j] - 2.0 * src[idx];
+                double lap_y = src[i * M + (j + 1)] + src[i * M + (j - 1)] - 2.0 * src[idx];
+                dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
             }
         }
 
@@ -77,7 +77,8 @@ void *barrier_worker(void *arg) {
   int steps = args->steps;
   int start_row = args->start_row;
   int end_row = args->end_row;
-  double factor = ALPHA * DT / (DX * DX);
+  double factor_x = ALPHA * DT / (DX * DX);
+  double factor_y = ALPHA * DT / (DY * DY);
 
   // 初期ポインタを引数から取得
   double *src = args->a->data;
@@ -98,10 +99,9 @@ void *barrier_worker(void *arg) {
         // i-1 (上), i+1 (下) のデータにアクセス。
         // i=r_startのとき、i-1は隣接スレッドの領域、i=r_end-1のとき、i+1は隣接スレッドの領域
         // 全てのデータは src (前ステップの結果) から読み込む
-        double laplacian = src[(i + 1) * M + j] + src[(i - 1) * M + j] +
-                           src[i * M + (j + 1)] + src[i * M + (j - 1)] -
-                           4.0 * src[idx];
-        dst[idx] = src[idx] + factor * laplacian;
+        double lap_x = src[(i + 1) * M + j] + src[(i - 1) * M + j] - 2.0 * src[idx];
+        double lap_y = src[i * M + (j + 1)] + src[i * M + (j - 1)] - 2.0 * src[idx];
+        dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
       }
     }
 
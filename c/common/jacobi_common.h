@@ -9,7 +9,8 @@
 #define TIME_STEPS 1000
 #define WARMUP_STEPS 10
 #define DT 0.1
-#define DX 1.0
+#define DX 1.0  // x方向（行番号 i の方向）のセル幅
+#define DY 1.0  // y方向（列番号 j の方向）のセル幅（Rust 版の grid::DY と同じ値にする）
 #define ALPHA 0.8
 
 // キャッシュラインアラインメント（64バイト）
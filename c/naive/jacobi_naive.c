@@ -17,7 +17,8 @@ void *naive_worker(void *arg) {
   double *dst = args->dst;
   int start_row = args->start_row;
   int end_row = args->end_row;
-  double factor = ALPHA * DT / (DX * DX);
+  double factor_x = ALPHA * DT / (DX * DX);
+  double factor_y = ALPHA * DT / (DY * DY);
 
   int r_start = (start_row < 1) ? 1 : start_row;
   int r_end = (end_row > N - 1) ? N - 1 : end_row;
@@ -25,10 +26,9 @@ void *naive_worker(void *arg) {
   for (int i = r_start; i < r_end; i++) {
     for (int j = 1; j < M - 1; j++) {
       int idx = i * M + j;
-      double laplacian = src[(i + 1) * M + j] + src[(i - 1) * M + j] +
-                         src[i * M + (j + 1)] + src[i * M + (j - 1)] -
-                         4.0 * src[idx];
-      dst[idx] = src[idx] + factor * laplacian;
+      double lap_x = src[(i + 1) * M + j] + src[(i - 1) * M + j] - 2.0 * src[idx];
+      double lap_y = src[i * M + (j + 1)] + src[i * M + (j - 1)] - 2.0 * src[idx];
+      dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
     }
   }
   return NULL;
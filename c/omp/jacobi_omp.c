@@ -4,7 +4,8 @@
 #include <string.h>
 
 void jacobi_step_omp(Grid *a, Grid *b, int steps) {
-  double factor = ALPHA * DT / (DX * DX);
+  double factor_x = ALPHA * DT / (DX * DX);
+  double factor_y = ALPHA * DT / (DY * DY);
   double *src = a->data;
   double *dst = b->data;
 
@@ -24,10 +25,9 @@ void jacobi_step_omp(Grid *a, Grid *b, int steps) {
 
         for (int j = 1; j < M - 1; j++) {
             int idx = i * M + j;
-            double laplacian = src[(i + 1) * M + j] + src[(i - 1) * M + j] +
-                               src[i * M + (j + 1)] + src[i * M + (j - 1)] -
-                               4.0 * src[idx];
-            dst[idx] = src[idx] + factor * laplacian;
+            double lap_x = src[(i + 1) * M + j] + src[(i - 1) * M + j] - 2.0 * src[idx];
+            double lap_y = src[i * M + (j + 1)] + src[i * M + (j - 1)] - 2.0 * src[idx];
+            dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
         }
     }
 
@@ -10,7 +10,8 @@
 
 // シングルスレッド版
 void jacobi_step_single(Grid *a, Grid *b, int steps) {
-    double factor = ALPHA * DT / (DX * DX);
+    double factor_x = ALPHA * DT / (DX * DX);
+    double factor_y = ALPHA * DT / (DY * DY);
     double *ptr_a = a->data;
     double *ptr_b = b->data;
 
@@ -18,10 +19,9 @@ void jacobi_step_single(Grid *a, Grid *b, int steps) {
         for (int i = 1; i < N - 1; i++) {
             for (int j = 1; j < M - 1; j++) {
                 int idx = i * M + j;
-                double laplacian = ptr_a[(i + 1) * M + j] + ptr_a[(i - 1) * M + j] +
-                                   ptr_a[i * M + (j + 1)] + ptr_a[i * M + (j - 1)] -
-                                   4.0 * ptr_a[idx];
-                ptr_b[idx] = ptr_a[idx] + factor * laplacian;
+                double lap_x = ptr_a[(i + 1) * M + j] + ptr_a[(i - 1) * M + j] - 2.0 * ptr_a[idx];
+                double lap_y = ptr_a[i * M + (j + 1)] + ptr_a[i * M + (j - 1)] - 2.0 * ptr_a[idx];
+                ptr_b[idx] = ptr_a[idx] + (factor_x * lap_x + factor_y * lap_y);
             }
         }
 
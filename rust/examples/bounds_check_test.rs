@@ -1,11 +1,11 @@
 // 境界チェックの有無を確認するテストコード
 
-fn safe_access(data: &Vec<f64>, i: usize, m: usize) -> f64 {
+fn safe_access(data: &[f64], i: usize, m: usize) -> f64 {
     let idx = i * m + 5;
     data[idx + 1] + data[idx - 1] + data[idx + m] + data[idx - m] - 4.0 * data[idx]
 }
 
-fn unsafe_access(data: &Vec<f64>, i: usize, m: usize) -> f64 {
+fn unsafe_access(data: &[f64], i: usize, m: usize) -> f64 {
     unsafe {
         let ptr = data.as_ptr();
         let idx = i * m + 5;
@@ -0,0 +1,234 @@
+use std::sync::{OnceLock, RwLock};
+
+/*
+  スレッドの CPU 固定（アフィニティ）
+
+  OS は帯分割のスレッドや Rayon のワーカーをコアや SMT の兄弟スレッドの間で移動させるため、測定値がばらつく。
+  PinPolicy で固定の方針を選び、各ワーカーは開始時に pin_worker(番号) を呼んで sched_setaffinity で自分を固定する。
+    - Compact : 同じ物理コアの SMT スレッドから順に詰める（キャッシュを共有させる）
+    - Scatter : ソケット・物理コアに1つずつ散らし、全コアを使ってから SMT の兄弟を使う
+    - NoSmt   : 各物理コアの最初の論理 CPU だけを使う
+    - Cores   : 論理 CPU 番号を明示する
+  ワーカー番号 i には、方針の並び順の i 番目（CPU 数で割った余り）の CPU を割り当てる。
+  方針はプロセス全体で1つ（set_policy）で、帯分割の各実装・BandPool・Rayon の start_handler から参照する。
+  新しいスレッドは作ったスレッドの CPU マスクを引き継ぐため、スレッドを作る側（メインスレッド）は固定しない。
+  Linux 以外では固定せず、PinPolicy::None と同じ動作になる。
+*/
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PinPolicy {
+    #[default]
+    None,
+    Compact,
+    Scatter,
+    NoSmt,
+    Cores(Vec<usize>),
+}
+
+impl PinPolicy {
+    /// "none" / "compact" / "scatter" / "nosmt" / "0,2,4"（CPU 番号のリスト）
+    pub fn parse(text: &str) -> Result<PinPolicy, String> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(PinPolicy::None),
+            "compact" => Ok(PinPolicy::Compact),
+            "scatter" => Ok(PinPolicy::Scatter),
+            "nosmt" | "no-smt" => Ok(PinPolicy::NoSmt),
+            list => {
+                let cores = list
+                    .split(',')
+                    .map(|c| c.trim().parse::<usize>().map_err(|_| format!("unknown pinning policy: {text}")))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PinPolicy::Cores(cores))
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            PinPolicy::None => "none".to_string(),
+            PinPolicy::Compact => "compact".to_string(),
+            PinPolicy::Scatter => "scatter".to_string(),
+            PinPolicy::NoSmt => "nosmt".to_string(),
+            PinPolicy::Cores(cores) => {
+                format!("cores={}", cores.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(","))
+            }
+        }
+    }
+
+    /// 方針に従った CPU の並び順（None なら空）
+    pub fn cpu_order(&self) -> Vec<usize> {
+        let mut cpus = topology().to_vec();
+        match self {
+            PinPolicy::None => Vec::new(),
+            PinPolicy::Cores(cores) => cores.clone(),
+            PinPolicy::Compact => {
+                cpus.sort_by_key(|c| (c.package, c.core, c.smt));
+                cpus.iter().map(|c| c.cpu).collect()
+            }
+            PinPolicy::Scatter => {
+                cpus.sort_by_key(|c| (c.smt, c.core_rank, c.package));
+                cpus.iter().map(|c| c.cpu).collect()
+            }
+            PinPolicy::NoSmt => {
+                cpus.retain(|c| c.smt == 0);
+                cpus.sort_by_key(|c| (c.package, c.core));
+                cpus.iter().map(|c| c.cpu).collect()
+            }
+        }
+    }
+
+    /// ワーカー番号 index に割り当てる CPU
+    pub fn cpu_for(&self, index: usize) -> Option<usize> {
+        let order = self.cpu_order();
+        (!order.is_empty()).then(|| order[index % order.len()])
+    }
+}
+
+static POLICY: RwLock<PinPolicy> = RwLock::new(PinPolicy::None);
+
+/// プロセス全体の固定方針を設定する（スレッドを起動する前に呼ぶ）
+///
+/// Cores に、このプロセスが使えない CPU が含まれていればエラーを返し、方針は変えない。
+pub fn set_policy(policy: PinPolicy) -> Result<(), String> {
+    // 固定でスレッドの CPU マスクが狭まる前に、使える CPU を調べておく
+    let allowed: Vec<usize> = topology().iter().map(|c| c.cpu).collect();
+    if let PinPolicy::Cores(cores) = &policy {
+        if cores.is_empty() {
+            return Err("no CPUs given".to_string());
+        }
+        if let Some(cpu) = cores.iter().find(|cpu| !allowed.contains(cpu)) {
+            return Err(format!("CPU {cpu} is not available to this process (allowed: {allowed:?})"));
+        }
+    }
+    *POLICY.write().unwrap() = policy;
+    Ok(())
+}
+
+pub fn policy() -> PinPolicy {
+    POLICY.read().unwrap().clone()
+}
+
+/// 現在のスレッドを、ワーカー番号 index に割り当てた CPU に固定する
+///
+/// 固定した CPU を返す。方針が None の場合や固定に失敗した場合は何もせず None を返す。
+pub fn pin_worker(index: usize) -> Option<usize> {
+    let cpu = POLICY.read().unwrap().cpu_for(index)?;
+    pin_current(cpu).ok().map(|_| cpu)
+}
+
+/// ベンチマーク出力用: 方針と、ワーカー番号 0..threads を実際に固定できた CPU
+///
+/// 番号ごとに一時スレッドを作って pin_worker を呼ぶので、失敗した番号は "-" になる（呼び出し元のスレッドは固定しない）。
+pub fn describe(threads: usize) -> String {
+    let policy = policy();
+    if policy == PinPolicy::None {
+        return format!("none (allowed CPUs: {})", topology().len());
+    }
+    let cpus: Vec<String> = (0..threads)
+        .map(|i| {
+            std::thread::spawn(move || pin_worker(i))
+                .join()
+                .unwrap()
+                .map_or("-".to_string(), |c| c.to_string())
+        })
+        .collect();
+    format!("{} -> [{}]", policy.label(), cpus.join(", "))
+}
+
+// 論理 CPU 1つ分の配置
+#[derive(Clone, Copy, Debug)]
+struct CpuInfo {
+    cpu: usize,
+    package: usize,
+    core: usize,      // パッケージ内の物理コア id
+    core_rank: usize, // パッケージ内での物理コアの順位（Scatter で各ソケットから交互に選ぶため）
+    smt: usize,       // 同じ物理コアの中での順位（0 が最初の論理 CPU）
+}
+
+// プロセスが使える論理 CPU と、その物理コア・ソケット（初回の呼び出しで調べてキャッシュする）
+fn topology() -> &'static [CpuInfo] {
+    static TOPOLOGY: OnceLock<Vec<CpuInfo>> = OnceLock::new();
+    TOPOLOGY.get_or_init(|| {
+        let read = |cpu: usize, name: &str| {
+            std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{cpu}/topology/{name}"))
+                .ok()
+                .and_then(|s| s.trim().parse::<usize>().ok())
+        };
+
+        let mut cpus: Vec<CpuInfo> = allowed_cpus()
+            .into_iter()
+            .map(|cpu| CpuInfo {
+                cpu,
+                package: read(cpu, "physical_package_id").unwrap_or(0),
+                core: read(cpu, "core_id").unwrap_or(cpu),
+                core_rank: 0,
+                smt: 0,
+            })
+            .collect();
+
+        let snapshot = cpus.clone();
+        for info in cpus.iter_mut() {
+            info.smt = snapshot
+                .iter()
+                .filter(|o| o.package == info.package && o.core == info.core && o.cpu < info.cpu)
+                .count();
+            let mut cores: Vec<usize> = snapshot.iter().filter(|o| o.package == info.package).map(|o| o.core).collect();
+            cores.sort_unstable();
+            cores.dedup();
+            info.core_rank = cores.iter().position(|&c| c == info.core).unwrap_or(0);
+        }
+        cpus
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> Vec<usize> {
+    // SAFETY: cpu_set_t はゼロ初期化で空集合になる。サイズは cpu_set_t そのもの
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return (0..std::thread::available_parallelism().map_or(1, |n| n.get())).collect();
+        }
+        (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allowed_cpus() -> Vec<usize> {
+    (0..std::thread::available_parallelism().map_or(1, |n| n.get())).collect()
+}
+
+/// 現在のスレッドを1つの論理 CPU に固定する
+#[cfg(target_os = "linux")]
+pub fn pin_current(cpu: usize) -> std::io::Result<()> {
+    if cpu >= libc::CPU_SETSIZE as usize {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+    }
+    // SAFETY: cpu は CPU_SETSIZE 未満。pid 0 は呼び出したスレッド自身を表す
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut set);
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current(_cpu: usize) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+/// 現在のスレッドが動いている論理 CPU
+#[cfg(target_os = "linux")]
+pub fn current_cpu() -> Option<usize> {
+    // SAFETY: 引数のない問い合わせ
+    let cpu = unsafe { libc::sched_getcpu() };
+    (cpu >= 0).then_some(cpu as usize)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn current_cpu() -> Option<usize> {
+    None
+}
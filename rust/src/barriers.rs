@@ -0,0 +1,171 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Barrier, Condvar, Mutex};
+use std::thread::{self, Thread};
+
+/*
+  待機するタイプ（ブロッキング）のバリア
+
+  std::sync::Barrier と、スピンで待つアトミックカウンタの中間にあたる同期方法を比べるため、
+  同じインターフェース（BandBarrier）で次のバリアを用意する。
+    - Barrier        : 標準ライブラリのバリア（比較の基準）
+    - CondvarBarrier : Mutex + Condvar の世代（generation）付きバリア
+    - FutexBarrier   : 到着数と世代を AtomicU32 で持ち、最後に着いたスレッドが世代を進めて futex で全員を起こす
+                       （Linux 以外では futex の代わりに yield_now で待つ）
+    - ParkBarrier    : 各スレッドが開始時に自分の Thread を登録し、最後に着いたスレッドが他の全員を unpark する
+  世代を使うのは、起きたスレッドが次のバリアに先に着いても、前の世代を待っているスレッドと混ざらないようにするため。
+*/
+
+pub trait BandBarrier: Sync {
+    const NAME: &'static str;
+
+    fn new(threads: usize) -> Self;
+
+    /// スレッド id が最初の wait の前に1回呼ぶ
+    fn register(&self, _id: usize) {}
+
+    /// 全スレッドが到着するまで待つ
+    fn wait(&self, id: usize);
+}
+
+impl BandBarrier for Barrier {
+    const NAME: &'static str = "std Barrier";
+
+    fn new(threads: usize) -> Self {
+        Barrier::new(threads)
+    }
+
+    fn wait(&self, _id: usize) {
+        Barrier::wait(self);
+    }
+}
+
+pub struct CondvarBarrier {
+    state: Mutex<(usize, u64)>, // (到着したスレッド数, 世代)
+    cvar: Condvar,
+    threads: usize,
+}
+
+impl BandBarrier for CondvarBarrier {
+    const NAME: &'static str = "Condvar";
+
+    fn new(threads: usize) -> Self {
+        CondvarBarrier { state: Mutex::new((0, 0)), cvar: Condvar::new(), threads }
+    }
+
+    fn wait(&self, _id: usize) {
+        let mut state = self.state.lock().unwrap();
+        let generation = state.1;
+        state.0 += 1;
+        if state.0 == self.threads {
+            *state = (0, generation + 1);
+            self.cvar.notify_all();
+        } else {
+            while state.1 == generation {
+                state = self.cvar.wait(state).unwrap();
+            }
+        }
+    }
+}
+
+pub struct FutexBarrier {
+    arrived: AtomicU32,
+    generation: AtomicU32,
+    threads: u32,
+}
+
+impl BandBarrier for FutexBarrier {
+    const NAME: &'static str = "futex";
+
+    fn new(threads: usize) -> Self {
+        FutexBarrier { arrived: AtomicU32::new(0), generation: AtomicU32::new(0), threads: threads as u32 }
+    }
+
+    fn wait(&self, _id: usize) {
+        // 到着を数える前に世代を読む（後で読むと、最後のスレッドが進めた後の世代を待ってしまう）
+        let generation = self.generation.load(Ordering::Acquire);
+        if self.arrived.fetch_add(1, Ordering::AcqRel) + 1 == self.threads {
+            // 他の全員は generation が変わるまで待っているので、先に到着数を戻してよい
+            self.arrived.store(0, Ordering::Relaxed);
+            self.generation.store(generation.wrapping_add(1), Ordering::Release);
+            futex_wake(&self.generation);
+        } else {
+            while self.generation.load(Ordering::Acquire) == generation {
+                futex_wait(&self.generation, generation);
+            }
+        }
+    }
+}
+
+// word がまだ expected なら眠る（値が変わっていればすぐ戻る）
+#[cfg(target_os = "linux")]
+fn futex_wait(word: &AtomicU32, expected: u32) {
+    // SAFETY: word は呼び出しの間有効な 32 ビットの整数
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            std::ptr::null::<libc::timespec>(),
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn futex_wake(word: &AtomicU32) {
+    // SAFETY: futex_wait と同じアドレスで眠っているスレッドを全員起こす
+    unsafe {
+        libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG, i32::MAX);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn futex_wait(_word: &AtomicU32, _expected: u32) {
+    thread::yield_now();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn futex_wake(_word: &AtomicU32) {}
+
+pub struct ParkBarrier {
+    arrived: AtomicUsize,
+    generation: AtomicUsize,
+    handles: Vec<Mutex<Option<Thread>>>,
+}
+
+impl BandBarrier for ParkBarrier {
+    const NAME: &'static str = "park/unpark";
+
+    fn new(threads: usize) -> Self {
+        ParkBarrier {
+            arrived: AtomicUsize::new(0),
+            generation: AtomicUsize::new(0),
+            handles: (0..threads).map(|_| Mutex::new(None)).collect(),
+        }
+    }
+
+    fn register(&self, id: usize) {
+        *self.handles[id].lock().unwrap() = Some(thread::current());
+    }
+
+    fn wait(&self, id: usize) {
+        let generation = self.generation.load(Ordering::Acquire);
+        if self.arrived.fetch_add(1, Ordering::AcqRel) + 1 == self.handles.len() {
+            self.arrived.store(0, Ordering::Relaxed);
+            self.generation.store(generation.wrapping_add(1), Ordering::Release);
+            // 他の全員は wait に入っている（= register 済み）。park より先に unpark しても起きられる
+            for (other, handle) in self.handles.iter().enumerate() {
+                if other != id
+                    && let Some(thread) = handle.lock().unwrap().as_ref()
+                {
+                    thread.unpark();
+                }
+            }
+        } else {
+            // park は理由なく戻ることがあるので、世代が変わったかを毎回確かめる
+            while self.generation.load(Ordering::Acquire) == generation {
+                thread::park();
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use jacobi_rust::implementations::safe::runge_kutta::Integrator;
+use jacobi_rust::validation::{observed_orders, spatial_convergence, temporal_convergence, AnalyticCase, ValidationRun};
+
+fn main() {
+    println!("=== 解析解による検証 ===");
+
+    let gaussian = AnalyticCase::Gaussian { amplitude: 1.0, sigma0: 0.05 };
+    let sine = AnalyticCase::SineMode { kx: 1, ky: 1 };
+
+    // 空間収束: 拡散数を固定し、時間誤差の小さいRK4で格子を細かくする
+    for (case, t_end) in [(gaussian, 0.002), (sine, 0.05)] {
+        println!("\n--- 空間収束: {} (t = {}) ---", case.label(), t_end);
+        let runs = spatial_convergence(&case, &[17, 33, 65, 129], 0.1, t_end, Integrator::Rk4);
+        print_runs(&runs, &observed_orders(&runs, |r| r.dx));
+    }
+
+    // 時間収束: 格子を固定し、空間離散化後の厳密解と比べる
+    for integrator in Integrator::ALL {
+        println!("\n--- 時間収束: {} / {} (n = 17, t = 0.05) ---", integrator.label(), sine.label());
+        let runs = temporal_convergence(&sine, 17, &[1e-3, 5e-4, 2.5e-4, 1.25e-4], 0.05, integrator);
+        print_runs(&runs, &observed_orders(&runs, |r| r.dt));
+    }
+}
+
+fn print_runs(runs: &[ValidationRun], orders: &[f64]) {
+    println!("{:>5} {:>10} {:>10} {:>7} {:>12} {:>12} {:>12} {:>7}", "n", "dx", "dt", "steps", "L1", "L2", "Linf", "order");
+    for (k, run) in runs.iter().enumerate() {
+        let order = if k == 0 { "-".to_string() } else { format!("{:.2}", orders[k - 1]) };
+        println!("{:>5} {:>10.3e} {:>10.3e} {:>7} {:>12.4e} {:>12.4e} {:>12.4e} {:>7}",
+            run.n, run.dx, run.dt, run.steps, run.norms.l1, run.norms.l2, run.norms.linf, order);
+    }
+}
@@ -0,0 +1,189 @@
+use std::ops::Range;
+use std::sync::Mutex;
+use crate::grid::{band_ranges, N, M};
+
+/*
+  2次元ブロック分割（Px × Py のプロセスグリッド）
+
+  帯分割は境界が上下の2行だけだが、帯1つあたりの交換量は常に M 要素になる。
+  スレッド数が多いと、格子を縦横のブロックに分けた方が1ブロックあたりのハローの周長が短くなる。
+    - 上下のハローは行の一部なので連続している
+    - 左右のハローは列の一部なので M 要素おきに散らばっており、交換バッファに詰めて（pack）から渡す
+  各ブロックは周囲に1セルのゴーストを付けた (rows+2) × (cols+2) の専用バッファで計算する。
+*/
+
+/// プロセスグリッド: 行方向に px 個、列方向に py 個のブロックに分ける
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessGrid {
+    pub px: usize,
+    pub py: usize,
+}
+
+impl ProcessGrid {
+    pub fn new(px: usize, py: usize) -> Self {
+        ProcessGrid {
+            px: px.clamp(1, N),
+            py: py.clamp(1, M),
+        }
+    }
+
+    /// num_threads = px·py となる分け方のうち、ハローの総量 (px-1)·M + (py-1)·N が最小のもの
+    pub fn for_threads(num_threads: usize) -> Self {
+        let n = num_threads.max(1);
+        (1..=n)
+            .filter(|px| n.is_multiple_of(*px))
+            .map(|px| ProcessGrid::new(px, n / px))
+            .min_by_key(|g| (g.px - 1) * M + (g.py - 1) * N)
+            .unwrap()
+    }
+
+    pub fn count(&self) -> usize {
+        self.px * self.py
+    }
+
+    /// ブロックの一覧（行優先、id = pi·py + pj）
+    pub fn blocks(&self) -> Vec<Block> {
+        let row_ranges = band_ranges(N, self.px);
+        let col_ranges = band_ranges(M, self.py);
+        let mut blocks = Vec::with_capacity(self.count());
+        for (pi, rows) in row_ranges.iter().enumerate() {
+            for (pj, cols) in col_ranges.iter().enumerate() {
+                let id = pi * self.py + pj;
+                blocks.push(Block {
+                    id,
+                    rows: rows.clone(),
+                    cols: cols.clone(),
+                    up: (pi > 0).then(|| id - self.py),
+                    down: (pi + 1 < self.px).then(|| id + self.py),
+                    left: (pj > 0).then(|| id - 1),
+                    right: (pj + 1 < self.py).then(|| id + 1),
+                });
+            }
+        }
+        blocks
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}x{}", self.px, self.py)
+    }
+}
+
+/// 1つのブロックの担当範囲と隣接ブロックの id
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub id: usize,
+    pub rows: Range<usize>,
+    pub cols: Range<usize>,
+    pub up: Option<usize>,
+    pub down: Option<usize>,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+}
+
+impl Block {
+    /// ゴーストを含めた専用バッファの1行の要素数
+    pub fn stride(&self) -> usize {
+        self.cols.len() + 2
+    }
+
+    pub fn local_len(&self) -> usize {
+        (self.rows.len() + 2) * self.stride()
+    }
+
+    /// 同期が必要な隣接ブロック
+    pub fn neighbors(&self) -> impl Iterator<Item = usize> {
+        [self.up, self.down, self.left, self.right].into_iter().flatten()
+    }
+
+    /// 全体の格子から担当範囲を専用バッファに取り出す（ゴーストは 0）
+    pub fn load(&self, global: &[f64]) -> Vec<f64> {
+        let stride = self.stride();
+        let mut local = vec![0.0; self.local_len()];
+        for (l, i) in self.rows.clone().enumerate() {
+            local[(l + 1) * stride + 1..(l + 1) * stride + 1 + self.cols.len()]
+                .copy_from_slice(&global[i * M + self.cols.start..i * M + self.cols.end]);
+        }
+        local
+    }
+
+    /// 専用バッファの担当範囲を全体の格子に書き戻す
+    pub fn store(&self, local: &[f64], global: &mut [f64]) {
+        let stride = self.stride();
+        for (l, i) in self.rows.clone().enumerate() {
+            global[i * M + self.cols.start..i * M + self.cols.end]
+                .copy_from_slice(&local[(l + 1) * stride + 1..(l + 1) * stride + 1 + self.cols.len()]);
+        }
+    }
+}
+
+// ブロック1つ分の書き出し先（上下は行、左右は詰めた列）
+struct Edges {
+    top: Mutex<Vec<f64>>,
+    bottom: Mutex<Vec<f64>>,
+    left: Mutex<Vec<f64>>,
+    right: Mutex<Vec<f64>>,
+}
+
+/// ブロックの4辺の交換バッファ
+///
+/// 各ブロックは自分の担当範囲の4辺を publish で書き出し、receive で隣接ブロックの辺をゴーストに取り込む。
+pub struct BlockHalo {
+    edges: Vec<Edges>,
+}
+
+impl BlockHalo {
+    pub fn new(blocks: &[Block]) -> Self {
+        BlockHalo {
+            edges: blocks
+                .iter()
+                .map(|b| Edges {
+                    top: Mutex::new(vec![0.0; b.cols.len()]),
+                    bottom: Mutex::new(vec![0.0; b.cols.len()]),
+                    left: Mutex::new(vec![0.0; b.rows.len()]),
+                    right: Mutex::new(vec![0.0; b.rows.len()]),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn publish(&self, block: &Block, local: &[f64]) {
+        let stride = block.stride();
+        let (h, w) = (block.rows.len(), block.cols.len());
+        let edges = &self.edges[block.id];
+
+        edges.top.lock().unwrap().copy_from_slice(&local[stride + 1..stride + 1 + w]);
+        edges.bottom.lock().unwrap().copy_from_slice(&local[h * stride + 1..h * stride + 1 + w]);
+
+        // 左右の列は stride おきに並んでいるので1要素ずつ詰める
+        let mut left = edges.left.lock().unwrap();
+        let mut right = edges.right.lock().unwrap();
+        for l in 0..h {
+            left[l] = local[(l + 1) * stride + 1];
+            right[l] = local[(l + 1) * stride + w];
+        }
+    }
+
+    pub fn receive(&self, block: &Block, local: &mut [f64]) {
+        let stride = block.stride();
+        let (h, w) = (block.rows.len(), block.cols.len());
+
+        if let Some(up) = block.up {
+            local[1..1 + w].copy_from_slice(&self.edges[up].bottom.lock().unwrap());
+        }
+        if let Some(down) = block.down {
+            local[(h + 1) * stride + 1..(h + 1) * stride + 1 + w].copy_from_slice(&self.edges[down].top.lock().unwrap());
+        }
+        if let Some(left) = block.left {
+            let packed = self.edges[left].right.lock().unwrap();
+            for l in 0..h {
+                local[(l + 1) * stride] = packed[l];
+            }
+        }
+        if let Some(right) = block.right {
+            let packed = self.edges[right].left.lock().unwrap();
+            for l in 0..h {
+                local[(l + 1) * stride + w + 1] = packed[l];
+            }
+        }
+    }
+}
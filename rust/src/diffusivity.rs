@@ -0,0 +1,139 @@
+use aligned_vec::AVec;
+use crate::grid::{Align64, ALPHA, DT, DX, DY, N, M};
+use crate::stencil::Stencil;
+
+/*
+  空間的に変化する拡散係数 α(i,j)
+
+  セル間の面の係数はセル値の調和平均 2·α1·α2 / (α1 + α2) で離散化する。
+  （熱伝導率が大きく異なる材料の境界で、流束が小さい側に支配されるようにするため）
+
+  カーネルから見て余計な演算が増えないよう、面係数には Δt/Δx²（下の面）、Δt/Δy²（右の面）をあらかじめ掛けておく:
+    u' = u + down[idx]·(u_下 - u) + down[idx-M]·(u_上 - u) + right[idx]·(u_右 - u) + right[idx-1]·(u_左 - u)
+  定数係数の5点ステンシルと比べ、1セルあたり2本分の係数配列の読み込みが増える。
+*/
+
+pub const COPPER_ALPHA: f64 = ALPHA;         // 銅配線
+pub const FR4_ALPHA: f64 = ALPHA / 700.0;    // ガラスエポキシ基板（銅との比は実物の熱拡散率の比に合わせる）
+
+#[derive(Clone, Debug)]
+pub struct Diffusivity {
+    pub alpha: AVec<f64, Align64>, // セルごとの拡散係数
+    pub down: AVec<f64, Align64>,  // (i,j)-(i+1,j) 間の面係数 × Δt/Δx²
+    pub right: AVec<f64, Align64>, // (i,j)-(i,j+1) 間の面係数 × Δt/Δy²
+}
+
+impl Diffusivity {
+    pub fn from_fn(f: impl Fn(usize, usize) -> f64) -> Self {
+        let alpha = AVec::from_iter(64, (0..N * M).map(|idx| f(idx / M, idx % M)));
+        let scale_x = DT / (DX * DX);
+        let scale_y = DT / (DY * DY);
+
+        let down = AVec::from_iter(64, (0..N * M).map(|idx| {
+            if idx / M + 1 < N { scale_x * harmonic_mean(alpha[idx], alpha[idx + M]) } else { 0.0 }
+        }));
+        let right = AVec::from_iter(64, (0..N * M).map(|idx| {
+            if idx % M + 1 < M { scale_y * harmonic_mean(alpha[idx], alpha[idx + 1]) } else { 0.0 }
+        }));
+
+        Diffusivity { alpha, down, right }
+    }
+
+    /// 全域で同じ値（定数係数の実装と同じ問題になる）
+    pub fn uniform(alpha: f64) -> Self {
+        Self::from_fn(|_, _| alpha)
+    }
+
+    /// FR4基板の中央を横切る銅配線（熱源の行を含む帯）
+    pub fn copper_trace() -> Self {
+        let width = (N / 32).max(2);
+        let top = N / 2 - width / 2;
+        Self::from_fn(|i, _| if (top..top + width).contains(&i) { COPPER_ALPHA } else { FR4_ALPHA })
+    }
+
+    /// 陽解法の安定条件: 各セルで面係数の和が1以下
+    pub fn is_stable(&self) -> bool {
+        (1..N - 1).all(|i| {
+            (1..M - 1).all(|j| {
+                let idx = i * M + j;
+                self.down[idx] + self.down[idx - M] + self.right[idx] + self.right[idx - 1] <= 1.0
+            })
+        })
+    }
+
+    /// 内部セル idx の更新値（境界チェックあり）
+    #[inline(always)]
+    pub fn update(&self, idx: usize, v: f64, up: f64, down: f64, left: f64, right: f64) -> f64 {
+        v + self.down[idx] * (down - v)
+            + self.down[idx - M] * (up - v)
+            + self.right[idx] * (right - v)
+            + self.right[idx - 1] * (left - v)
+    }
+
+    /// 内部行 i（大域行番号）を1行分更新する。up/cur/down は i-1, i, i+1 行の値
+    ///
+    /// 帯分割の実装では up/down に境界交換用のバッファをそのまま渡せる。
+    #[inline(always)]
+    pub fn update_row(&self, i: usize, up: &[f64], cur: &[f64], down: &[f64], dst: &mut [f64]) {
+        for j in 1..M - 1 {
+            dst[j] = self.update(i * M + j, cur[j], up[j], down[j], cur[j - 1], cur[j + 1]);
+        }
+    }
+
+    /// update の境界チェックなし版
+    ///
+    /// # Safety
+    /// idx は内部セル（1 ≤ i < N-1, 1 ≤ j < M-1）のインデックスであること
+    #[inline(always)]
+    pub unsafe fn update_unchecked(&self, idx: usize, v: f64, up: f64, down: f64, left: f64, right: f64) -> f64 {
+        unsafe {
+            v + *self.down.get_unchecked(idx) * (down - v)
+                + *self.down.get_unchecked(idx - M) * (up - v)
+                + *self.right.get_unchecked(idx) * (right - v)
+                + *self.right.get_unchecked(idx - 1) * (left - v)
+        }
+    }
+}
+
+// 各実装の本体はステンシルを引数に取るため、可変係数版は Diffusivity をそのまま渡す
+impl Stencil for Diffusivity {
+    const RADIUS: usize = 1;
+    const NAME: &'static str = "variable α";
+
+    #[inline(always)]
+    fn apply_row<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        self.update_row(i, row(i - 1), row(i), row(i + 1), dst);
+    }
+
+    #[inline(always)]
+    unsafe fn apply_row_unchecked<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        let (up, cur, down) = (row(i - 1), row(i), row(i + 1));
+        // SAFETY: i は内部行、j は内部列なので、係数の添字 i*M+j とその上下左右は範囲内
+        unsafe {
+            for j in 1..M - 1 {
+                *dst.get_unchecked_mut(j) = self.update_unchecked(
+                    i * M + j,
+                    *cur.get_unchecked(j),
+                    *up.get_unchecked(j),
+                    *down.get_unchecked(j),
+                    *cur.get_unchecked(j - 1),
+                    *cur.get_unchecked(j + 1),
+                );
+            }
+        }
+    }
+
+    // 面係数は Δt に比例するため、面係数の和が最大のセルで和が1になる Δt が上限
+    fn dt_limit(&self) -> f64 {
+        let worst = (1..N - 1)
+            .flat_map(|i| (1..M - 1).map(move |j| i * M + j))
+            .map(|idx| self.down[idx] + self.down[idx - M] + self.right[idx] + self.right[idx - 1])
+            .fold(0.0_f64, f64::max);
+        if worst == 0.0 { f64::INFINITY } else { DT / worst }
+    }
+}
+
+#[inline(always)]
+fn harmonic_mean(a: f64, b: f64) -> f64 {
+    if a + b == 0.0 { 0.0 } else { 2.0 * a * b / (a + b) }
+}
@@ -0,0 +1,83 @@
+use std::ops::Range;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::grid::{band_ranges, Grid, M, N};
+
+/*
+  帯ごとに分けた2枚の格子（safe な double buffer）
+
+  barrier_unsafe は2枚の格子を生ポインタで共有し、「自分の帯だけに書く」ことをプログラマが保証している。
+  BandedDoubleBuffer は2枚の格子をそれぞれ帯ごとの RwLock<Vec<f64>> に分けて持ち、
+  ステップ step の帯 id に対して、次の借用だけをまとめて貸し出す（StepView）。
+    - 読み: 読む側のバッファ（step の偶奇で決まる）の自分の帯と、上下の隣接する帯（境界行を読むため）
+    - 書き: 書く側のバッファの自分の帯（&mut）
+  ステップの間を Barrier で区切れば、同じ帯を同時に読む側と書く側が取ることは無いため、ロックは常に待たずに取れる。
+  境界行を共有バッファにコピーせず、隣の帯を直接読む点が barrier_parallel との違い。
+*/
+
+pub struct BandedDoubleBuffer {
+    bands: Vec<Range<usize>>,
+    buffers: [Vec<RwLock<Vec<f64>>>; 2],
+}
+
+/// ステップ1回分の、1つの帯の借用
+pub struct StepView<'a> {
+    pub rows: Range<usize>,
+    pub above: Option<RwLockReadGuard<'a, Vec<f64>>>,
+    pub src: RwLockReadGuard<'a, Vec<f64>>,
+    pub below: Option<RwLockReadGuard<'a, Vec<f64>>>,
+    pub dst: RwLockWriteGuard<'a, Vec<f64>>,
+}
+
+impl BandedDoubleBuffer {
+    /// grid を band_ranges(N, parts) の帯に分けて2枚とも初期化する
+    pub fn from_grid(grid: &Grid, parts: usize) -> Self {
+        let bands = band_ranges(N, parts.clamp(1, N));
+        let split = || {
+            bands
+                .iter()
+                .map(|rows| RwLock::new(grid.data[rows.start * M..rows.end * M].to_vec()))
+                .collect::<Vec<_>>()
+        };
+        let buffers = [split(), split()];
+        BandedDoubleBuffer { bands, buffers }
+    }
+
+    pub fn band_count(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// ステップ step で帯 id が使う借用（step が偶数なら 0 枚目を読んで 1 枚目に書く）
+    pub fn step_view(&self, step: usize, id: usize) -> StepView<'_> {
+        let src = &self.buffers[step % 2];
+        let dst = &self.buffers[(step + 1) % 2];
+        StepView {
+            rows: self.bands[id].clone(),
+            above: id.checked_sub(1).map(|up| src[up].read().unwrap()),
+            src: src[id].read().unwrap(),
+            below: src.get(id + 1).map(|down| down.read().unwrap()),
+            dst: dst[id].write().unwrap(),
+        }
+    }
+
+    /// steps ステップ後の結果（最後に書いたバッファ）を grid に書き戻す
+    pub fn store(&self, steps: usize, grid: &mut Grid) {
+        for (rows, band) in self.bands.iter().zip(&self.buffers[steps % 2]) {
+            grid.data[rows.start * M..rows.end * M].copy_from_slice(&band.read().unwrap());
+        }
+    }
+}
+
+/// (上の帯の最終行, 自分の帯, 下の帯の先頭行, 書き込み先の自分の帯)
+pub type BandParts<'a> = (Option<&'a [f64]>, &'a [f64], Option<&'a [f64]>, &'a mut [f64]);
+
+impl StepView<'_> {
+    /// 読み書きする行を BandParts に分けて借りる
+    pub fn parts(&mut self) -> BandParts<'_> {
+        (
+            self.above.as_deref().map(|above| &above[above.len() - M..]),
+            &self.src,
+            self.below.as_deref().map(|below| &below[..M]),
+            &mut self.dst,
+        )
+    }
+}
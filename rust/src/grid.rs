@@ -1,30 +1,161 @@
-use aligned_vec::{AVec, ConstAlign};
+use aligned_vec::ConstAlign;
+use crate::memory::{GridBuffer, PageMode};
+use crate::shared_grid::SharedPtr;
 
 pub const N: usize = 256;  // x方向セル数
 pub const M: usize = 256;  // y方向セル数
 pub const TIME_STEPS: usize = 1000;  //ステップ数
 pub const WARMUP_STEPS: usize = 10; //ウォームアップ数
 pub const DT: f64 = 0.1;  //時間刻み幅
-pub const DX: f64 = 1.0;  //グリッドの1セルの「物理的距離」
+pub const DX: f64 = 1.0;  //グリッドの1セルの「物理的距離」（x方向 = 行番号 i の方向）
+pub const DY: f64 = 1.0;  //y方向（列番号 j の方向）のセル幅。DX と違う値にすると長方形セルになる
 pub const ALPHA: f64 = 0.8;  // 拡散係数
 
-// キャッシュラインアラインメント（64バイト）
-type Align64 = ConstAlign<64>;
+/// 陽解法（前進オイラー）が安定となる時間刻みの上限
+///
+/// 5点ステンシルでは α·Δt·(1/Δx² + 1/Δy²) ≤ 1/2 が安定条件となる（Δx = Δy なら α·Δt/Δx² ≤ 1/4）。
+pub fn explicit_dt_limit() -> f64 {
+    0.5 / (ALPHA * (1.0 / (DX * DX) + 1.0 / (DY * DY)))
+}
+
+/// 指定した時間刻みで陽解法が安定かどうか
+pub fn is_explicit_stable(dt: f64) -> bool {
+    dt <= explicit_dt_limit()
+}
+
+/// 固定値（ディリクレ条件）のセルかどうか: 外周と中心の熱源
+///
+/// 陰解法では未知数から除外するセルの判定に使う。
+#[inline(always)]
+pub fn is_dirichlet(i: usize, j: usize) -> bool {
+    i == 0 || i == N - 1 || j == 0 || j == M - 1 || (i == N / 2 && j == M / 2)
+}
+
+/// 可変サイズの計算領域（ステージ計算や検証用のソルバーで使う）
+///
+/// factor_x は α·Δt/Δx²（行方向の隣接 i±1 に掛かる）、factor_y は α·Δt/Δy²（列方向の隣接 j±1 に掛かる）。
+/// heat_source は固定値を保つセルのインデックス。
+#[derive(Clone, Copy, Debug)]
+pub struct Domain {
+    pub rows: usize,
+    pub cols: usize,
+    pub factor_x: f64,
+    pub factor_y: f64,
+    pub heat_source: Option<usize>,
+}
+
+impl Domain {
+    /// Grid と同じ N×M、中心に熱源のある領域
+    pub fn standard(dt: f64) -> Self {
+        Domain {
+            rows: N,
+            cols: M,
+            factor_x: ALPHA * dt / (DX * DX),
+            factor_y: ALPHA * dt / (DY * DY),
+            heat_source: Some((N / 2) * M + M / 2),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 0..len を parts 個の連続区間にほぼ均等に分割する（帯分割の担当範囲）
+pub fn band_ranges(len: usize, parts: usize) -> Vec<std::ops::Range<usize>> {
+    let parts = parts.max(1);
+    (0..parts)
+        .map(|p| (p * len / parts)..((p + 1) * len / parts))
+        .collect()
+}
+
+// キャッシュラインアラインメント（64バイト）。Grid 以外の配列で使う（Grid は memory::PageMode で選ぶ）
+pub type Align64 = ConstAlign<64>;
 
 #[derive(Clone,Debug)]
 pub struct Grid {
-    pub data: AVec<f64, Align64>,
+    pub data: GridBuffer,
 }
 
 impl Default for Grid {
     fn default() -> Self {
         Grid {
-            data: AVec::from_iter(64, std::iter::repeat(0.0).take(N * M)),
+            data: GridBuffer::zeroed(N * M, PageMode::Default),
+        }
+    }
+}
+
+/// グリッドのメモリをどのスレッドが最初に書き込むか
+///
+/// Linux ではページは最初に書き込んだスレッドが動いている NUMA ノードに置かれる（first-touch）。
+/// MainThread ではメインスレッドが全体を 0 で埋めるため、全ページが1つのノードに集まる。
+/// FirstTouch では band_ranges(N, threads) の各帯を、その帯を計算するワーカー（同じ番号で CPU 固定される）が 0 で埋める。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    #[default]
+    MainThread,
+    FirstTouch { threads: usize },
+}
+
+impl Placement {
+    pub fn label(&self) -> String {
+        match self {
+            Placement::MainThread => "main-thread".to_string(),
+            Placement::FirstTouch { threads } => format!("first-touch x{threads}"),
         }
     }
 }
 
 impl Grid {
+    /// 0 で埋めたグリッドを placement の方法で確保する
+    pub fn zeroed(placement: Placement) -> Self {
+        Grid::allocate(placement, PageMode::Default)
+    }
+
+    /// 0 で埋めたグリッドを、pages のページで確保し placement の方法で最初に書き込む
+    pub fn allocate(placement: Placement, pages: PageMode) -> Self {
+        let threads = match placement {
+            Placement::MainThread => return Grid { data: GridBuffer::zeroed(N * M, pages) },
+            Placement::FirstTouch { threads } => threads.clamp(1, N),
+        };
+
+        // 確保しただけのページはまだどのノードにも置かれていない
+        // SAFETY: 下のスレッドで全要素を書き込む
+        let mut data = unsafe { GridBuffer::uninit(N * M, pages) };
+        let ptr = SharedPtr::new(data.as_mut_ptr());
+
+        std::thread::scope(|scope| {
+            for (id, rows) in band_ranges(N, threads).into_iter().enumerate() {
+                scope.spawn(move || {
+                    crate::affinity::pin_worker(id);
+                    // SAFETY: 各スレッドは自分の帯の行だけに書き込む
+                    unsafe {
+                        std::ptr::write_bytes(ptr.get().add(rows.start * M), 0, rows.len() * M);
+                    }
+                });
+            }
+        });
+
+        // 帯は 0..N を隙間なく覆うので、N*M 要素すべてが 0 になっている（0.0 のビット列は全ビット 0）
+        Grid { data }
+    }
+
+    /// 中心に熱源を置いたグリッドを placement の方法で確保する
+    pub fn with_placement(placement: Placement) -> Self {
+        Grid::with_options(placement, PageMode::Default)
+    }
+
+    /// 中心に熱源を置いたグリッドを、pages のページで確保し placement の方法で最初に書き込む
+    pub fn with_options(placement: Placement, pages: PageMode) -> Self {
+        let mut grid = Grid::allocate(placement, pages);
+        grid.data[(N / 2) * M + (M / 2)] = 100.0;
+        grid
+    }
+
     pub fn new() -> Self {
         let mut grid = Grid::default();
         // 格子の中心に熱源を設定
@@ -88,8 +219,8 @@ impl Grid {
             temp_data.push(f64::from_le_bytes(buf));
         }
 
-        // AVecに変換
-        let data = AVec::from_iter(64, temp_data.into_iter());
+        // 格子のバッファに変換
+        let data = GridBuffer::from_slice(&temp_data, PageMode::Default);
 
         Ok(Grid { data })
     }
@@ -0,0 +1,58 @@
+use aligned_vec::AVec;
+use crate::grid::{Align64, ALPHA, DX, DY};
+
+/*
+  3次元格子（7点ステンシル用）
+
+  インデックスは idx = (k * NX + i) * NY + j。k が z 方向で、1枚の xy 平面（PLANE 要素）が連続して並ぶ。
+  並列版は z 方向に平面単位で帯（スラブ）分割するため、交換する境界は1平面 = NX·NY 要素になる。
+  2次元の帯分割（境界は1行 = M 要素）と比べ、担当領域の体積に対する境界の割合が大きい。
+
+  外周の面は0固定、中心セルを熱源として100固定にする（2次元の Grid と同じ問題設定）。
+*/
+
+pub const NX: usize = 64;  // x方向セル数
+pub const NY: usize = 64;  // y方向セル数
+pub const NZ: usize = 64;  // z方向セル数
+pub const PLANE: usize = NX * NY;  // xy平面1枚の要素数
+pub const DZ: f64 = 1.0;  // z方向のセル幅（x, y は grid.rs の DX, DY）
+
+/// 熱源セルのインデックス
+pub const CENTER3: usize = (NZ / 2) * PLANE + (NX / 2) * NY + NY / 2;
+
+/// 3次元の陽解法が安定となる時間刻みの上限: α·Δt·(1/Δx² + 1/Δy² + 1/Δz²) ≤ 1/2
+pub fn explicit_dt_limit3() -> f64 {
+    0.5 / (ALPHA * (1.0 / (DX * DX) + 1.0 / (DY * DY) + 1.0 / (DZ * DZ)))
+}
+
+/// 拡散数 (α·Δt/Δx², α·Δt/Δy², α·Δt/Δz²)
+pub fn diffusion_factors3(dt: f64) -> (f64, f64, f64) {
+    (ALPHA * dt / (DX * DX), ALPHA * dt / (DY * DY), ALPHA * dt / (DZ * DZ))
+}
+
+#[derive(Clone, Debug)]
+pub struct Grid3 {
+    pub data: AVec<f64, Align64>,
+}
+
+impl Default for Grid3 {
+    fn default() -> Self {
+        Grid3 {
+            data: AVec::from_iter(64, std::iter::repeat_n(0.0, NZ * PLANE)),
+        }
+    }
+}
+
+impl Grid3 {
+    pub fn new() -> Self {
+        let mut grid = Grid3::default();
+        // 格子の中心に熱源を設定
+        grid.data[CENTER3] = 100.0;
+        grid
+    }
+
+    #[inline(always)]
+    pub fn index(i: usize, j: usize, k: usize) -> usize {
+        (k * NX + i) * NY + j
+    }
+}
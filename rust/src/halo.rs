@@ -0,0 +1,127 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::grid::{N, M};
+use crate::stencil::Stencil;
+
+/*
+  ロックを使わない安全な境界行（ゴースト行）のバッファ
+
+  f64 のビット列を AtomicU64 に入れて1要素ずつ読み書きする。
+  Mutex<Vec<f64>> と違ってロックの取得が無く、unsafe も使わない。
+  各要素は Relaxed で読み書きし、行全体の受け渡しの順序は、書いた後のカウンタの Release / バリアと、
+  読む前のカウンタの Acquire / バリアで保証する（書いている最中の行を読まないのは同期側の責任）。
+*/
+
+pub struct AtomicRow {
+    cells: Box<[AtomicU64]>,
+}
+
+impl AtomicRow {
+    pub fn new(len: usize) -> Self {
+        AtomicRow { cells: (0..len).map(|_| AtomicU64::new(0.0f64.to_bits())).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// row の内容を書き込む
+    #[inline(always)]
+    pub fn store(&self, row: &[f64]) {
+        for (cell, &v) in self.cells.iter().zip(row) {
+            cell.store(v.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, j: usize) -> f64 {
+        f64::from_bits(self.cells[j].load(Ordering::Relaxed))
+    }
+
+    pub fn load_into(&self, dst: &mut [f64]) {
+        for (d, cell) in dst.iter_mut().zip(self.cells.iter()) {
+            *d = f64::from_bits(cell.load(Ordering::Relaxed));
+        }
+    }
+}
+
+/// 隣の帯の境界データを待つタイミング
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HaloSchedule {
+    /// 相手の境界データが揃うのを待ってから全行を計算する
+    WaitFirst,
+    /// 相手に依存しない内部行を先に計算し、その後で待って境界行を計算する
+    Overlap,
+}
+
+impl HaloSchedule {
+    pub const ALL: [HaloSchedule; 2] = [HaloSchedule::WaitFirst, HaloSchedule::Overlap];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HaloSchedule::WaitFirst => "Wait-first",
+            HaloSchedule::Overlap => "Overlap",
+        }
+    }
+}
+
+/// 帯の担当行（own）と上下のゴースト行をまとめ、全体の行番号で1行ずつ引けるようにしたもの
+///
+/// 帯分割の実装は、隣の帯から受け取ったゴースト行をここに渡すだけで Stencil::apply_row を使える。
+/// 上端（下端）の帯では above（below）は空でよい。
+pub struct BandRows<'a> {
+    first: usize,
+    own: &'a [f64],
+    above: &'a [f64],
+    below: &'a [f64],
+}
+
+impl<'a> BandRows<'a> {
+    /// rows は own が担当する全体の行。above は rows.start の直前、below は rows.end 以降の行
+    pub fn new(rows: &Range<usize>, own: &'a [f64], above: &'a [f64], below: &'a [f64]) -> Self {
+        assert_eq!(own.len(), rows.len() * M, "band size mismatch");
+        BandRows { first: rows.start, own, above, below }
+    }
+
+    /// 全体の k 行目
+    #[inline(always)]
+    pub fn row(&self, k: usize) -> &'a [f64] {
+        let end = self.first + self.own.len() / M;
+        if k < self.first {
+            let l = self.above.len() - (self.first - k) * M;
+            &self.above[l..l + M]
+        } else if k < end {
+            let l = (k - self.first) * M;
+            &self.own[l..l + M]
+        } else {
+            let l = (k - end) * M;
+            &self.below[l..l + M]
+        }
+    }
+
+    /// rows（全体の行番号）のうち、外周 S::RADIUS 行を除いた行を dst（帯の担当行）に書く。
+    /// 熱源の固定は呼び出し側で行う
+    #[inline(always)]
+    pub fn apply<S: Stencil>(&self, stencil: &S, rows: Range<usize>, dst: &mut [f64]) {
+        for i in rows.start.max(S::RADIUS)..rows.end.min(N - S::RADIUS) {
+            let l = (i - self.first) * M;
+            stencil.apply_row(i, |k| self.row(k), &mut dst[l..l + M]);
+        }
+    }
+}
+
+/// data（N*M 要素の格子）を bands（0..N を順に覆う行の区間）ごとの可変スライスに分ける
+pub fn split_bands<'a>(mut data: &'a mut [f64], bands: &[Range<usize>]) -> Vec<&'a mut [f64]> {
+    bands
+        .iter()
+        .map(|rows| {
+            let (band, rest) = std::mem::take(&mut data).split_at_mut(rows.len() * M);
+            data = rest;
+            band
+        })
+        .collect()
+}
@@ -6,4 +6,9 @@ pub mod unsafe_impl {
     pub mod barrier_unsafe;
     pub mod rayon_unsafe;
     pub mod single_unsafe;
+    pub mod adi_unsafe;
+    pub mod three_d_unsafe;
+    pub mod cache_oblivious;
+    pub mod pooled;
+    pub mod blocking;
 }
@@ -0,0 +1,201 @@
+use rayon::prelude::*;
+use crate::grid::{is_dirichlet, Grid, ALPHA, DT, DX, DY, N, M};
+
+/*
+  ADI法（Peaceman–Rachford）
+
+  1ステップを半ステップ2回に分け、行方向と列方向を交互に陰的に解く。
+    前半: (I - hy·Lj) u* = (I + hx·Li) u^n    … 各行ごとの三重対角方程式（行スイープ）
+    後半: (I - hx·Li) u' = (I + hy·Lj) u*     … 各列ごとの三重対角方程式（列スイープ）
+  ここで hx = α·Δt / (2·Δx²)、hy = α·Δt / (2·Δy²)。各行・各列はThomas法で直接解くため内部反復は不要。
+
+  バッファの使い方: 前半で a → b、後半で b → a と書くため、ステップ終了時に結果は常に a にある。
+
+  列スイープは行優先の配列を列方向に走査するためキャッシュ効率が悪い。
+  ColumnSweep で走査方法を切り替えて、その影響を測定できるようにしている。
+*/
+
+/// 列スイープのメモリ走査方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnSweep {
+    /// 1列ずつ解く。各アクセスが M 要素おきになる
+    Strided,
+    /// width 列をまとめて、行ごとに連続アクセスしながら前進消去・後退代入する
+    Blocked { width: usize },
+}
+
+impl ColumnSweep {
+    pub fn width(&self) -> usize {
+        match self {
+            ColumnSweep::Strided => 1,
+            ColumnSweep::Blocked { width } => (*width).max(1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AdiConfig {
+    pub dt: f64,
+    pub column_sweep: ColumnSweep,
+}
+
+impl Default for AdiConfig {
+    fn default() -> Self {
+        AdiConfig {
+            dt: DT,
+            column_sweep: ColumnSweep::Blocked { width: 64 },
+        }
+    }
+}
+
+/// 半ステップの係数 (hx, hy)。陰的に解く方向の三重対角行列の対角成分は 1 + 2h
+pub(crate) fn adi_coefficients(dt: f64) -> (f64, f64) {
+    (0.5 * ALPHA * dt / (DX * DX), 0.5 * ALPHA * dt / (DY * DY))
+}
+
+pub fn adi_single(a: &mut Grid, b: &mut Grid, steps: usize, config: &AdiConfig) {
+    let (hx, hy) = adi_coefficients(config.dt);
+    let width = config.column_sweep.width();
+
+    let mut cp_row = vec![0.0; M];
+    let mut cp_block = vec![0.0; N * width];
+
+    for _ in 0..steps {
+        // 前半: 行スイープ a → b
+        for (i, dst_row) in b.data.chunks_mut(M).enumerate() {
+            row_sweep(&a.data, i, dst_row, &mut cp_row, hx, hy);
+        }
+
+        // 後半: 列スイープ b → a
+        let mut j0 = 0;
+        while j0 < M {
+            let j1 = (j0 + width).min(M);
+            column_sweep_block(&b.data, j0, j1, &mut a.data, M, j0, &mut cp_block, hx, hy);
+            j0 = j1;
+        }
+    }
+}
+
+pub fn adi_rayon(a: &mut Grid, b: &mut Grid, steps: usize, config: &AdiConfig) {
+    let (hx, hy) = adi_coefficients(config.dt);
+    let width = config.column_sweep.width();
+
+    // 列ブロックごとの作業領域（解の一時置き場 と Thomas法の係数 c'）。ステップ間で使い回す
+    let mut blocks: Vec<(usize, usize, Vec<f64>, Vec<f64>)> = (0..M)
+        .step_by(width)
+        .map(|j0| {
+            let j1 = (j0 + width).min(M);
+            let w = j1 - j0;
+            (j0, j1, vec![0.0; N * w], vec![0.0; N * w])
+        })
+        .collect();
+
+    for _ in 0..steps {
+        // 前半: 行スイープ。各行は独立なので行単位で分配する
+        let src = &a.data[..];
+        b.data
+            .par_chunks_mut(M)
+            .enumerate()
+            .for_each_init(|| vec![0.0; M], |cp, (i, dst_row)| {
+                row_sweep(src, i, dst_row, cp, hx, hy);
+            });
+
+        // 後半: 列スイープ。列ブロックごとに連続な一時領域へ解き、
+        // その後行単位で a に書き戻す（列方向の書き込みをスレッド間で分割できないため）
+        let src = &b.data[..];
+        blocks.par_iter_mut().for_each(|(j0, j1, out, cp)| {
+            let w = *j1 - *j0;
+            column_sweep_block(src, *j0, *j1, out, w, 0, cp, hx, hy);
+        });
+
+        let blocks_ref = &blocks;
+        a.data.par_chunks_mut(M).enumerate().for_each(|(i, dst_row)| {
+            for (j0, j1, out, _) in blocks_ref {
+                let w = j1 - j0;
+                dst_row[*j0..*j1].copy_from_slice(&out[i * w..(i + 1) * w]);
+            }
+        });
+    }
+}
+
+// 行 i の三重対角方程式を解いて dst_row に書く
+// 右辺 (I + hx·Li) u を dst_row に作ってから、その場でThomas法を適用する
+pub(crate) fn row_sweep(src: &[f64], i: usize, dst_row: &mut [f64], cp: &mut [f64], hx: f64, hy: f64) {
+    let diag = 1.0 + 2.0 * hy;
+    if i == 0 || i == N - 1 {
+        dst_row.copy_from_slice(&src[i * M..(i + 1) * M]);
+        return;
+    }
+
+    // 前進消去
+    for j in 0..M {
+        let idx = i * M + j;
+        let (lower, center, upper, d) = if is_dirichlet(i, j) {
+            (0.0, 1.0, 0.0, src[idx])
+        } else {
+            (-hy, diag, -hy, src[idx] + hx * (src[idx - M] - 2.0 * src[idx] + src[idx + M]))
+        };
+        if j == 0 {
+            cp[j] = upper / center;
+            dst_row[j] = d / center;
+        } else {
+            let m = center - lower * cp[j - 1];
+            cp[j] = upper / m;
+            dst_row[j] = (d - lower * dst_row[j - 1]) / m;
+        }
+    }
+
+    // 後退代入
+    for j in (0..M - 1).rev() {
+        dst_row[j] -= cp[j] * dst_row[j + 1];
+    }
+}
+
+// 列 [j0, j1) の三重対角方程式をまとめて解く
+// 出力は out[i * out_stride + out_col0 + (j - j0)] に書く。
+// 単一スレッド版では out = 書き込み先グリッド（stride M）、並列版では列ブロック専用の一時領域（stride w）
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn column_sweep_block(
+    src: &[f64],
+    j0: usize,
+    j1: usize,
+    out: &mut [f64],
+    out_stride: usize,
+    out_col0: usize,
+    cp: &mut [f64],
+    hx: f64,
+    hy: f64,
+) {
+    let w = j1 - j0;
+    let diag = 1.0 + 2.0 * hx;
+
+    // 前進消去（行ごとに w 列を連続アクセス）
+    for i in 0..N {
+        for j in j0..j1 {
+            let idx = i * M + j;
+            let k = j - j0;
+            let o = i * out_stride + out_col0 + k;
+            let (lower, center, upper, d) = if is_dirichlet(i, j) {
+                (0.0, 1.0, 0.0, src[idx])
+            } else {
+                (-hx, diag, -hx, src[idx] + hy * (src[idx - 1] - 2.0 * src[idx] + src[idx + 1]))
+            };
+            if i == 0 {
+                cp[k] = upper / center;
+                out[o] = d / center;
+            } else {
+                let m = center - lower * cp[(i - 1) * w + k];
+                cp[i * w + k] = upper / m;
+                out[o] = (d - lower * out[o - out_stride]) / m;
+            }
+        }
+    }
+
+    // 後退代入
+    for i in (0..N - 1).rev() {
+        for k in 0..w {
+            let o = i * out_stride + out_col0 + k;
+            out[o] -= cp[i * w + k] * out[o + out_stride];
+        }
+    }
+}
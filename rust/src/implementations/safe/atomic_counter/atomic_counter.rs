@@ -1,174 +1,192 @@
 use std::thread;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
-
+use std::ops::Range;
+use crate::diffusivity::Diffusivity;
+use crate::grid::{Grid, N, M};
+use crate::affinity;
+use crate::halo::{split_bands, AtomicRow, BandRows, HaloSchedule};
+use crate::stencil::{FivePoint, Stencil};
+use crate::sync::{SpinCounter, StepCounter, StepSignal, WaitStats, WaitStrategy};
+
+// カウンタは AtomicUsize そのもの（SpinCounter）で、256 回スピンしたら yield する
 pub fn atomic_counter(a: &mut Grid, b: &mut Grid, steps: usize) {
-    let mid = N / 2;
-    let factor = ALPHA * DT / (DX * DX);
-
-    // 同期用のアトミックカウンター
-    // "Ready": 境界データの書き込みが完了したことを示す
-    // "Done":  計算が完了し、境界バッファを解放して良いことを示す
-    let upper_ready = Arc::new(AtomicUsize::new(0));
-    let lower_ready = Arc::new(AtomicUsize::new(0));
-    let upper_done = Arc::new(AtomicUsize::new(0));
-    let lower_done = Arc::new(AtomicUsize::new(0));
+    atomic_counter_scheduled(a, b, &FivePoint, steps, SpinCounter::new, HaloSchedule::WaitFirst, false);
+}
 
-    // 境界データ共有用 (MutexはUnsafe回避のためのコンテナとして使用)
-    let boundary_mid_minus_1 = Arc::new(Mutex::new(vec![0.0; M])); 
-    let boundary_mid = Arc::new(Mutex::new(vec![0.0; M]));
+// atomic_counter の待ち方を strategy で選ぶ版（カウンタは sync.rs の StepCounter）
+pub fn atomic_counter_with(a: &mut Grid, b: &mut Grid, steps: usize, strategy: WaitStrategy) {
+    atomic_counter_stencil(a, b, &FivePoint, steps, strategy);
+}
 
-    // ゼロコピー: データを可変スライスとして分割
-    let (a_upper, a_lower) = a.data.split_at_mut(mid * M);
-    let (b_upper, b_lower) = b.data.split_at_mut(mid * M);
-
-    thread::scope(|scope| {
-        // --- 上半分スレッド ---
-        let u_ready = upper_ready.clone();
-        let l_ready = lower_ready.clone();
-        let u_done = upper_done.clone();
-        let l_done = lower_done.clone();
-        
-        let my_bound_writer = boundary_mid_minus_1.clone();
-        let other_bound_reader = boundary_mid.clone();
-
-        scope.spawn(move || {
-            let mut src = a_upper;
-            let mut dst = b_upper;
-            let rows = mid;
-
-            for step in 1..=steps { // stepカウントを1から開始にしてわかりやすくする Inclusive Range (以下)	1 から steps まで
-                // 1. 境界データを共有バッファに書き込み
-                {
-                    let mut writer = my_bound_writer.lock().unwrap();
-                    let row_idx = rows - 1;
-                    writer.copy_from_slice(&src[row_idx * M..(row_idx + 1) * M]);
-                }
+// 可変拡散係数版。同期プロトコルは atomic_counter と同じ
+pub fn atomic_counter_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize) {
+    atomic_counter_scheduled(a, b, coef, steps, SpinCounter::new, HaloSchedule::WaitFirst, false);
+}
 
-                // 通知: 「データ準備よし」
-                u_ready.store(step, Ordering::Release);
+// セルの更新式を stencil で選ぶ版（待ち方は strategy、カウンタは StepCounter）
+pub fn atomic_counter_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize, strategy: WaitStrategy) {
+    atomic_counter_scheduled(a, b, stencil, steps, || StepCounter::new(strategy), HaloSchedule::WaitFirst, false);
+}
 
-                // 待機: 相手のデータ準備ができるまでスピン待機
-                wait_for_step(&l_ready, step);
+// atomic_counter の計測版。schedule で境界データを待つタイミングを切り替え、待ち時間を返す
+// Overlap では、相手の境界行を待つ間に内部行の計算が進むため、その分だけ待ち時間が隠れる
+pub fn atomic_counter_overlap(a: &mut Grid, b: &mut Grid, steps: usize, schedule: HaloSchedule) -> WaitStats {
+    atomic_counter_scheduled(a, b, &FivePoint, steps, SpinCounter::new, schedule, true)
+}
 
-                // 2. 計算フェーズ
-                // 内部
-                for i in 1..rows - 1 {
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let laplacian = src[idx - M] + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
-                
-                // 境界 (相手のバッファを読む)
-                {
-                    let reader = other_bound_reader.lock().unwrap();
-                    let i = rows - 1;
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let down_val = reader[j];
-                        let laplacian = src[idx - M] + down_val + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
+// 本体。上半分 (帯0) と下半分 (帯1) の2スレッドで、カウンタは counter() で作る
+// timed のときだけ待機を計測する（計測しない版では時刻を取らない）
+fn atomic_counter_scheduled<S: Stencil, C: StepSignal>(
+    a: &mut Grid,
+    b: &mut Grid,
+    stencil: &S,
+    steps: usize,
+    counter: impl Fn() -> C,
+    schedule: HaloSchedule,
+    timed: bool,
+) -> WaitStats {
+    let mid = N / 2;
+    let bands = [0..mid, mid..N];
+    let exchange = CounterExchange::new(&bands, S::RADIUS, counter);
 
-                // 通知: 「計算完了（バッファ読み終わった）」
-                u_done.store(step, Ordering::Release);
+    // ゼロコピー: データを可変スライスとして分割
+    let a_bands = split_bands(&mut a.data, &bands);
+    let b_bands = split_bands(&mut b.data, &bands);
+
+    let stats = thread::scope(|scope| {
+        let workers: Vec<_> = a_bands
+            .into_iter()
+            .zip(b_bands)
+            .enumerate()
+            .map(|(id, (a_band, b_band))| {
+                let exchange = &exchange;
+                scope.spawn(move || {
+                    affinity::pin_worker(id);
+                    let mut stats = timed.then(WaitStats::default);
+                    exchange.run_band(id, a_band, b_band, stencil, steps, schedule, stats.as_mut());
+                    stats.unwrap_or_default()
+                })
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().unwrap()).fold(WaitStats::default(), WaitStats::merge)
+    });
 
-                // 待機: 相手も計算を終えるまで待つ
-                // これがないと、次のループで自分が書き込む際、相手がまだ読んでる最中かもしれない
-                wait_for_step(&l_done, step);
+    // 奇数ステップ終了時の書き戻し処理が必要であればここで行う
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+    stats
+}
 
-                std::mem::swap(&mut src, &mut dst);
-            }
-        });
-
-        // --- 下半分スレッド ---
-        let u_ready = upper_ready.clone();
-        let l_ready = lower_ready.clone();
-        let u_done = upper_done.clone();
-        let l_done = lower_done.clone();
-
-        let my_bound_writer = boundary_mid.clone();
-        let other_bound_reader = boundary_mid_minus_1.clone();
-
-        scope.spawn(move || {
-            let mut src = a_lower;
-            let mut dst = b_lower;
-            let rows = N - mid;
-
-            for step in 1..=steps {
-                // 1. 境界書き込み
-                {
-                    let mut writer = my_bound_writer.lock().unwrap();
-                    writer.copy_from_slice(&src[0..M]);
-                }
+// 帯ごとのカウンタと境界バッファ。隣接する帯とだけ同期する（帯の数は2に限らない）
+pub(crate) struct CounterExchange<C = StepCounter> {
+    bands: Vec<Range<usize>>,
+    width: usize,
+    // 同期用のアトミックカウンター（帯ごと）
+    // "Ready": 境界データの書き込みが完了したことを示す
+    // "Done":  計算が完了し、境界バッファを解放して良いことを示す
+    ready: Vec<C>,
+    done: Vec<C>,
+    // 境界データ共有用 (ロックの無い AtomicRow。読み書きの順序はカウンタが保証する)
+    // 帯 id は先頭 w 行を first[id] に、末尾 w 行を last[id] に書く
+    first: Vec<AtomicRow>,
+    last: Vec<AtomicRow>,
+}
 
-                // 通知: Ready
-                l_ready.store(step, Ordering::Release);
+impl<C: StepSignal> CounterExchange<C> {
+    pub(crate) fn new(bands: &[Range<usize>], width: usize, counter: impl Fn() -> C) -> Self {
+        assert!(bands.iter().all(|rows| rows.len() >= 2 * width), "each band needs at least 2 * RADIUS rows");
+        let count = bands.len();
+        CounterExchange {
+            bands: bands.to_vec(),
+            width,
+            ready: (0..count).map(|_| counter()).collect(),
+            done: (0..count).map(|_| counter()).collect(),
+            first: (0..count).map(|_| AtomicRow::new(width * M)).collect(),
+            last: (0..count).map(|_| AtomicRow::new(width * M)).collect(),
+        }
+    }
 
-                // 待機: 相手のReady
-                wait_for_step(&u_ready, step);
+    // 上下の帯の counters[id±1] が step に達するまで待つ
+    fn wait_neighbors(&self, counters: &[C], id: usize, step: usize, mut stats: Option<&mut WaitStats>) {
+        if id > 0 {
+            counters[id - 1].wait_counted(step, stats.as_deref_mut());
+        }
+        if let Some(below) = counters.get(id + 1) {
+            below.wait_counted(step, stats);
+        }
+    }
 
-                // 2. 計算
-                for i in 1..rows - 1 {
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let laplacian = src[idx - M] + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
+    // 帯 id（a_band / b_band はその行）を steps ステップ進める。全部の帯が同時に呼ぶこと
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn run_band<S: Stencil>(
+        &self,
+        id: usize,
+        a_band: &mut [f64],
+        b_band: &mut [f64],
+        stencil: &S,
+        steps: usize,
+        schedule: HaloSchedule,
+        mut stats: Option<&mut WaitStats>,
+    ) {
+        assert!(S::RADIUS <= self.width, "stencil radius exceeds the exchanged rows");
+        let w = self.width;
+        let count = self.bands.len();
+        let rows = self.bands[id].clone();
+        let mut src = a_band;
+        let mut dst = b_band;
+        let n = src.len();
+
+        // 相手の境界行の写し（端の帯では外側は空）
+        let mut above = vec![0.0; if id > 0 { w * M } else { 0 }];
+        let mut below = vec![0.0; if id + 1 < count { w * M } else { 0 }];
+        // 相手の境界行を読む行（上端・下端の w 行）と、読まない内部行
+        let top_end = if id > 0 { rows.start + w } else { rows.start };
+        let bottom_start = if id + 1 < count { rows.end - w } else { rows.end };
+
+        for step in 1..=steps { // stepカウントを1から開始にしてわかりやすくする Inclusive Range (以下)	1 から steps まで
+            // 1. 境界データを共有バッファに書き込み
+            self.first[id].store(&src[..w * M]);
+            self.last[id].store(&src[n - w * M..]);
+
+            // 通知: 「データ準備よし」
+            self.ready[id].publish(step);
+
+            // Overlap: 相手のデータを使わない内部行を、待つ前に計算する
+            if schedule == HaloSchedule::Overlap {
+                BandRows::new(&rows, src, &[], &[]).apply(stencil, top_end..bottom_start, dst);
+            }
 
-                // 境界 (相手のバッファを読む)
-                {
-                    let reader = other_bound_reader.lock().unwrap();
-                    let i = 0;
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let up_val = reader[j];
-                        let laplacian = up_val + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
+            // 待機: 相手のデータ準備ができるまでスピン待機
+            self.wait_neighbors(&self.ready, id, step, stats.as_deref_mut());
 
-                if N / 2 >= mid {
-                    let local_row = N / 2 - mid;
-                    dst[local_row * M + M / 2] = 100.0;
+            // 2. 計算フェーズ (相手の境界行はゴースト行として渡す)
+            if id > 0 {
+                self.last[id - 1].load_into(&mut above);
+            }
+            if id + 1 < count {
+                self.first[id + 1].load_into(&mut below);
+            }
+            let band = BandRows::new(&rows, src, &above, &below);
+            match schedule {
+                HaloSchedule::WaitFirst => band.apply(stencil, rows.clone(), dst),
+                HaloSchedule::Overlap => {
+                    band.apply(stencil, rows.start..top_end, dst);
+                    band.apply(stencil, bottom_start..rows.end, dst);
                 }
+            }
 
-                // 通知: Done
-                l_done.store(step, Ordering::Release);
-
-                // 待機: 相手のDone
-                wait_for_step(&u_done, step);
-
-                std::mem::swap(&mut src, &mut dst);
+            if rows.contains(&(N / 2)) {
+                dst[(N / 2 - rows.start) * M + M / 2] = 100.0;
             }
-        });
-    });
 
-    // 奇数ステップ終了時の書き戻し処理が必要であればここで行う
-    if steps % 2 == 1 {
-        a.data.copy_from_slice(&b.data);
-    }
-}
+            // 通知: 「計算完了（バッファ読み終わった）」
+            self.done[id].publish(step);
 
-#[inline(always)]
-fn wait_for_step(counter: &AtomicUsize, step: usize) {
-    const SPIN_BEFORE_YIELD: usize = 256;
-    let mut spin = 0;
-    loop {
-        if counter.load(Ordering::Relaxed) >= step {
-            std::sync::atomic::fence(Ordering::Acquire);
-            break;
-        }
-        std::hint::spin_loop();
-        spin += 1;
-        if spin >= SPIN_BEFORE_YIELD {
-            spin = 0;
-            std::thread::yield_now();
+            // 待機: 相手も計算を終えるまで待つ
+            // これがないと、次のループで自分が書き込む際、相手がまだ読んでる最中かもしれない
+            self.wait_neighbors(&self.done, id, step, stats.as_deref_mut());
+
+            std::mem::swap(&mut src, &mut dst);
         }
     }
-}
\ No newline at end of file
+}
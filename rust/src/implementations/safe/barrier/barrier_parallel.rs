@@ -1,145 +1,127 @@
 // lib.rs (または main.rs)
+use std::ops::Range;
 use std::thread;
-use std::sync::{Arc, Barrier, Mutex};
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
+use std::sync::Barrier;
+use crate::diffusivity::Diffusivity;
+use crate::grid::{band_ranges, Grid, N, M};
+use crate::affinity;
+use crate::halo::{split_bands, AtomicRow, BandRows};
+use crate::stencil::{FivePoint, Stencil};
 
 /*
-  Rust: 安全な並列実装 (Mutex/Arc/Barrierを使用)
-  境界行のデータ交換にMutex+Copyを使用することで安全性を確保。
-  オーバーヘッドは大きくなる可能性があるが、データ競合は確実に回避される。
+  Rust: 安全な並列実装 (AtomicRow/Barrierを使用)
+  境界行のデータ交換は AtomicRow（halo.rs）へのコピーで行い、unsafe もロックも使わずに安全性を確保。
+  境界行の受け渡しの順序はバリアが保証するため、データ競合は確実に回避される。
+  交換する行数（ハロー幅）はステンシルの RADIUS 行。
 */
 
 pub fn barrier_parallel(a: &mut Grid, b: &mut Grid, steps: usize) {
+    barrier_parallel_stencil(a, b, &FivePoint, steps);
+}
+
+// 可変拡散係数版。境界交換とバリア同期は barrier_parallel と同じ
+pub fn barrier_parallel_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize) {
+    barrier_parallel_stencil(a, b, coef, steps);
+}
+
+// 本体。上半分 (Rows 0 to mid-1) と下半分 (Rows mid to N-1) の2スレッドで、セルの更新式は stencil で選ぶ
+pub fn barrier_parallel_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize) {
     let mid = N / 2;
-    let factor = ALPHA * DT / (DX * DX);
+    barrier_bands(a, b, stencil, steps, &[0..mid, mid..N]);
+}
 
-    let barrier = Arc::new(Barrier::new(2));
+// 帯の数を num_threads にした版（各帯は RADIUS 行以上になるように帯の数を抑える）
+pub fn barrier_parallel_bands<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize, num_threads: usize) {
+    let bands = band_ranges(N, num_threads.clamp(1, N / S::RADIUS));
+    barrier_bands(a, b, stencil, steps, &bands);
+}
 
-    // 境界データの交換用バッファ (ゴーストセル)
-    let boundary_mid_minus_1 = Arc::new(Mutex::new(vec![0.0; M])); 
-    let boundary_mid = Arc::new(Mutex::new(vec![0.0; M]));
+fn barrier_bands<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize, bands: &[Range<usize>]) {
+    let exchange = BarrierExchange::new(bands, S::RADIUS);
 
-    // aとbそれぞれのデータを上下に分割
-    let (a_upper, a_lower) = a.data.split_at_mut(mid * M);
-    let (b_upper, b_lower) = b.data.split_at_mut(mid * M);
+    // aとbそれぞれのデータを帯ごとに分割
+    let a_bands = split_bands(&mut a.data, bands);
+    let b_bands = split_bands(&mut b.data, bands);
 
     thread::scope(|scope| {
-        // --- スレッド1: 上半分 (Rows 0 to mid-1) ---
-        let barrier1 = barrier.clone();
-        let bound_write = boundary_mid_minus_1.clone(); // 自分が書く (最終行 mid-1)
-        let bound_read = boundary_mid.clone();          // 相手から読む (0行目 mid)
-        
-        scope.spawn(move || {
-            let mut src = a_upper;
-            let mut dst = b_upper;
-            let rows = mid; // ローカルな行数
-
-            for _step in 0..steps {
-                // 1. 自分の境界行（一番下の行: rows-1）を共有バッファに書き出す
-                {
-                    let mut writer = bound_write.lock().unwrap();
-                    let row_idx = rows - 1;
-                    // srcスライスの該当行をコピー
-                    writer.copy_from_slice(&src[row_idx * M..(row_idx + 1) * M]);
-                }
-
-                // バリア: 両者が境界を書き込むのを待つ
-                barrier1.wait();
-
-                // 2. 内部領域の計算 (行 1 .. rows-2)
-                for i in 1..rows - 1 {
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let laplacian = src[idx - M] + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
-
-                // 3. 境界行 (rows-1) の計算
-                // 自分の下(idx+M)は相手のバッファ(bound_read)から読み込む
-                {
-                    let reader = bound_read.lock().unwrap(); // 下半分の0行目(global mid行目)を取得
-                    let i = rows - 1;
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let down_val = reader[j]; // 共有バッファから読み取り
-                        
-                        let laplacian = src[idx - M] + down_val + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
-                
-                // 固定熱源
-                let mid_global_row = N / 2;
-                if mid_global_row < mid { // 上半分にある場合
-                    dst[mid_global_row * M + M/2] = 100.0;
-                }
-
-                // バリア: 計算完了待ち
-                barrier1.wait();
-
-                // 参照の入れ替え
-                std::mem::swap(&mut src, &mut dst);
-            }
-        });
-
-        // --- スレッド2: 下半分 (Rows mid to N-1) ---
-        let barrier2 = barrier.clone();
-        let bound_write = boundary_mid.clone();          // 自分が書く (0行目 mid)
-        let bound_read = boundary_mid_minus_1.clone();   // 相手から読む (最終行 mid-1)
-
-        scope.spawn(move || {
-            let mut src = a_lower;
-            let mut dst = b_lower;
-            let rows = N - mid; // ローカルな行数
-
-            for _step in 0..steps {
-                // 1. 自分の境界行（一番上の行: 0）を共有バッファに書き出す
-                {
-                    let mut writer = bound_write.lock().unwrap();
-                    // srcスライスの0行目をコピー
-                    writer.copy_from_slice(&src[0..M]);
-                }
-
-                barrier2.wait();
-
-                // 2. 内部領域の計算 (行 1 .. rows-2)
-                for i in 1..rows - 1 {
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let laplacian = src[idx - M] + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
-
-                // 3. 境界行 (0) の計算
-                // 自分の上(idx-M)は相手のバッファ(bound_read)から読み込む
-                {
-                    let reader = bound_read.lock().unwrap(); // 上半分の最終行を取得
-                    let i = 0;
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        let up_val = reader[j];
-
-                        let laplacian = up_val + src[idx + M] + src[idx - 1] + src[idx + 1] - 4.0 * src[idx];
-                        dst[idx] = src[idx] + factor * laplacian;
-                    }
-                }
-
-                // 固定熱源 (下半分にある場合 - 相対座標に変換)
-                let mid_global_row = N / 2;
-                if mid_global_row >= mid {
-                    let local_row = mid_global_row - mid;
-                    dst[local_row * M + M/2] = 100.0;
-                }
-
-                barrier2.wait();
-                std::mem::swap(&mut src, &mut dst);
-            }
-        });
+        for (id, (a_band, b_band)) in a_bands.into_iter().zip(b_bands).enumerate() {
+            let exchange = &exchange;
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                exchange.run_band(id, a_band, b_band, stencil, steps);
+            });
+        }
     });
 
     if steps % 2 == 1 {
         a.data.copy_from_slice(&b.data);
     }
-}
\ No newline at end of file
+}
+
+// 帯どうしの境界交換とバリア。スレッドの作り方（thread::scope か pooled のプール）とは独立している
+pub(crate) struct BarrierExchange {
+    bands: Vec<Range<usize>>,
+    width: usize,
+    barrier: Barrier,
+    // 境界データの交換用バッファ (ゴーストセル)。帯 id は先頭 w 行を first[id] に、末尾 w 行を last[id] に書く
+    first: Vec<AtomicRow>,
+    last: Vec<AtomicRow>,
+}
+
+impl BarrierExchange {
+    pub(crate) fn new(bands: &[Range<usize>], width: usize) -> Self {
+        assert!(bands.iter().all(|rows| rows.len() >= width), "each band needs at least RADIUS rows");
+        let count = bands.len();
+        BarrierExchange {
+            bands: bands.to_vec(),
+            width,
+            barrier: Barrier::new(count),
+            first: (0..count).map(|_| AtomicRow::new(width * M)).collect(),
+            last: (0..count).map(|_| AtomicRow::new(width * M)).collect(),
+        }
+    }
+
+    // 帯 id（a_band / b_band はその行）を steps ステップ進める。全部の帯が同時に呼ぶこと
+    pub(crate) fn run_band<S: Stencil>(&self, id: usize, a_band: &mut [f64], b_band: &mut [f64], stencil: &S, steps: usize) {
+        assert!(S::RADIUS <= self.width, "stencil radius exceeds the exchanged rows");
+        let w = self.width;
+        let count = self.bands.len();
+        let rows = self.bands[id].clone();
+        let mut src = a_band;
+        let mut dst = b_band;
+        let n = src.len();
+
+        // 相手の境界行の写し（端の帯では外側は空）
+        let mut above = vec![0.0; if id > 0 { w * M } else { 0 }];
+        let mut below = vec![0.0; if id + 1 < count { w * M } else { 0 }];
+
+        for _step in 0..steps {
+            // 1. 自分の境界行（先頭・末尾の w 行）を共有バッファに書き出す
+            self.first[id].store(&src[..w * M]);
+            self.last[id].store(&src[n - w * M..]);
+
+            // バリア: 全員が境界を書き込むのを待つ
+            self.barrier.wait();
+
+            // 2. 隣の帯の境界行を取り込み、担当行を計算する
+            if id > 0 {
+                self.last[id - 1].load_into(&mut above);
+            }
+            if id + 1 < count {
+                self.first[id + 1].load_into(&mut below);
+            }
+            BandRows::new(&rows, src, &above, &below).apply(stencil, rows.clone(), dst);
+
+            // 固定熱源
+            if rows.contains(&(N / 2)) {
+                dst[(N / 2 - rows.start) * M + M / 2] = 100.0;
+            }
+
+            // バリア: 次のステップで交換バッファを上書きする前に、全員の読み出しを待つ
+            self.barrier.wait();
+
+            // 参照の入れ替え
+            std::mem::swap(&mut src, &mut dst);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use crate::decomposition::{Block, BlockHalo, ProcessGrid};
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use crate::affinity;
+use crate::sync::{StepCounter, WaitStrategy};
+
+/*
+  2次元ブロック分割の並列実装（decomposition.rs の ProcessGrid / BlockHalo を使う）
+
+  各スレッドは1ブロックを担当し、ゴースト付きの専用バッファで計算する。
+    - block_barrier : 4辺を書き出した後、全スレッドで Barrier を待つ（1ステップに2回）
+    - block_p2p     : ブロックごとの ready / done カウンタで、上下左右の隣接ブロックだけを待つ
+*/
+
+// ブロックの行は全体の格子の中で M 要素おきに飛び飛びに並ぶため、帯のように連続した可変スライスとしては貸せない。
+// 各スレッドは Block::load でゴースト付きの専用バッファに取り出して計算し、最後に Block::store で a に書き戻すので、
+// 2枚目のグリッド _b は使わない
+pub fn block_barrier(a: &mut Grid, _b: &mut Grid, steps: usize, grid: &ProcessGrid) {
+    let blocks = grid.blocks();
+    let barrier = Arc::new(Barrier::new(blocks.len()));
+    let halo = Arc::new(BlockHalo::new(&blocks));
+
+    let results: Vec<Mutex<Vec<f64>>> = blocks.iter().map(|_| Mutex::new(Vec::new())).collect();
+    let initial: &[f64] = &a.data;
+
+    thread::scope(|scope| {
+        for block in &blocks {
+            let barrier = barrier.clone();
+            let halo = halo.clone();
+            let result = &results[block.id];
+
+            scope.spawn(move || {
+                affinity::pin_worker(block.id);
+                let mut src = block.load(initial);
+                let mut dst = src.clone();
+
+                for _step in 0..steps {
+                    // 1. 4辺を書き出す（左右は列を詰める）
+                    halo.publish(block, &src);
+
+                    // バリア: 全員が書き出すのを待つ
+                    barrier.wait();
+
+                    // 2. 隣接ブロックの辺をゴーストに取り込んで計算
+                    halo.receive(block, &mut src);
+                    update_block(&src, &mut dst, block);
+
+                    // バリア: 次のステップで交換バッファを上書きする前に、全員の読み出しを待つ
+                    barrier.wait();
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+
+                *result.lock().unwrap() = src;
+            });
+        }
+    });
+
+    for (block, result) in blocks.iter().zip(results) {
+        block.store(&result.into_inner().unwrap(), &mut a.data);
+    }
+}
+
+pub fn block_p2p(a: &mut Grid, _b: &mut Grid, steps: usize, grid: &ProcessGrid) {
+    let blocks = grid.blocks();
+    let count = blocks.len();
+    let halo = Arc::new(BlockHalo::new(&blocks));
+
+    // ready: 4辺の書き出し完了、done: 隣接ブロックの辺の読み出しと計算の完了
+    let ready: Arc<Vec<StepCounter>> = Arc::new((0..count).map(|_| StepCounter::new(WaitStrategy::default())).collect());
+    let done: Arc<Vec<StepCounter>> = Arc::new((0..count).map(|_| StepCounter::new(WaitStrategy::default())).collect());
+
+    let results: Vec<Mutex<Vec<f64>>> = blocks.iter().map(|_| Mutex::new(Vec::new())).collect();
+    let initial: &[f64] = &a.data;
+
+    thread::scope(|scope| {
+        for block in &blocks {
+            let halo = halo.clone();
+            let ready = ready.clone();
+            let done = done.clone();
+            let result = &results[block.id];
+
+            scope.spawn(move || {
+                affinity::pin_worker(block.id);
+                let mut src = block.load(initial);
+                let mut dst = src.clone();
+                let neighbors: Vec<usize> = block.neighbors().collect();
+
+                for step in 1..=steps {
+                    halo.publish(block, &src);
+                    ready[block.id].publish(step);
+
+                    for &n in &neighbors {
+                        ready[n].wait_for(step);
+                    }
+                    halo.receive(block, &mut src);
+                    update_block(&src, &mut dst, block);
+
+                    done[block.id].publish(step);
+                    for &n in &neighbors {
+                        done[n].wait_for(step);
+                    }
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+
+                *result.lock().unwrap() = src;
+            });
+        }
+    });
+
+    for (block, result) in blocks.iter().zip(results) {
+        block.store(&result.into_inner().unwrap(), &mut a.data);
+    }
+}
+
+// ブロックの担当範囲を更新する（外周のセルは計算しない）
+fn update_block(src: &[f64], dst: &mut [f64], block: &Block) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let stride = block.stride();
+    let j0 = block.cols.start.max(1);
+    let j1 = block.cols.end.min(M - 1);
+
+    for i in block.rows.start.max(1)..block.rows.end.min(N - 1) {
+        let row = (i - block.rows.start + 1) * stride;
+        for j in j0..j1 {
+            let idx = row + (j - block.cols.start + 1);
+            let lap_x = src[idx - stride] + src[idx + stride] - 2.0 * src[idx];
+            let lap_y = src[idx - 1] + src[idx + 1] - 2.0 * src[idx];
+            dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
+        }
+    }
+
+    if block.rows.contains(&(N / 2)) && block.cols.contains(&(M / 2)) {
+        dst[(N / 2 - block.rows.start + 1) * stride + 1 + M / 2 - block.cols.start] = 100.0;
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use crate::affinity;
+use crate::grid::{band_ranges, Grid, ALPHA, DT, DX, DY, N, M};
+use crate::spsc::{row_ring, RowConsumer, RowProducer};
+
+/*
+  メッセージパッシングによるハロー交換（MPI 風）
+
+  各スレッドは担当する帯を自分だけのバッファに持ち、格子のメモリを他のスレッドと一切共有しない。
+  ステップごとに、自分の帯の先頭行を上の帯へ、末尾行を下の帯へ送り、隣から届いた行をゴースト行に受け取る。
+  受け取りが同期を兼ねるため、バリアもカウンタも使わない。
+    - channel_mpsc : std::sync::mpsc のチャネルで行（Vec<f64>）を送る。送るたびに Vec を確保する
+    - channel_spsc : spsc.rs の有界リングバッファで送る。スロットは事前に確保され、capacity 行まで先に送れる
+*/
+
+/// 境界行を送る側
+pub trait RowSender: Send {
+    fn send_row(&mut self, row: &[f64]);
+}
+
+/// 境界行を受け取る側
+pub trait RowReceiver: Send {
+    fn recv_row(&mut self, dst: &mut [f64]);
+}
+
+impl RowSender for Sender<Vec<f64>> {
+    fn send_row(&mut self, row: &[f64]) {
+        self.send(row.to_vec()).expect("neighbor band hung up");
+    }
+}
+
+impl RowReceiver for Receiver<Vec<f64>> {
+    fn recv_row(&mut self, dst: &mut [f64]) {
+        dst.copy_from_slice(&self.recv().expect("neighbor band hung up"));
+    }
+}
+
+impl RowSender for RowProducer {
+    fn send_row(&mut self, row: &[f64]) {
+        self.push(row).expect("neighbor band hung up");
+    }
+}
+
+impl RowReceiver for RowConsumer {
+    fn recv_row(&mut self, dst: &mut [f64]) {
+        self.pop_into(dst).expect("neighbor band hung up");
+    }
+}
+
+// 格子を共有せず、隣の帯とは行をチャネルで送り合うので、各スレッドは自分の帯の src / dst を持って交互に使う。
+// そのため2枚目のグリッド _b は使わない
+pub fn channel_mpsc(a: &mut Grid, _b: &mut Grid, steps: usize, num_threads: usize) {
+    exchange_bands(a, steps, num_threads, mpsc::channel::<Vec<f64>>);
+}
+
+pub fn channel_spsc(a: &mut Grid, _b: &mut Grid, steps: usize, num_threads: usize, capacity: usize) {
+    exchange_bands(a, steps, num_threads, || row_ring(capacity, M));
+}
+
+// 1つの帯が持つ、隣接する帯との送受信の端点
+struct Links<S, R> {
+    to_up: Option<S>,
+    to_down: Option<S>,
+    from_up: Option<R>,
+    from_down: Option<R>,
+}
+
+fn exchange_bands<S, R>(a: &mut Grid, steps: usize, num_threads: usize, mut link: impl FnMut() -> (S, R))
+where
+    S: RowSender,
+    R: RowReceiver,
+{
+    let bands = band_ranges(N, num_threads.clamp(1, N));
+    let count = bands.len();
+
+    // 隣接する帯の組ごとに、上向きと下向きの2本の通信路を作る
+    let mut links: Vec<Links<S, R>> = (0..count)
+        .map(|_| Links { to_up: None, to_down: None, from_up: None, from_down: None })
+        .collect();
+    for id in 1..count {
+        let (down_tx, down_rx) = link();
+        links[id - 1].to_down = Some(down_tx);
+        links[id].from_up = Some(down_rx);
+        let (up_tx, up_rx) = link();
+        links[id].to_up = Some(up_tx);
+        links[id - 1].from_down = Some(up_rx);
+    }
+
+    let results: Vec<Mutex<Vec<f64>>> = bands.iter().map(|_| Mutex::new(Vec::new())).collect();
+    let initial: &[f64] = &a.data;
+
+    thread::scope(|scope| {
+        for ((id, rows), mut links) in bands.iter().cloned().enumerate().zip(links) {
+            let result = &results[id];
+
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let h = rows.len();
+
+                // [ゴースト1行][担当 h 行][ゴースト1行]
+                let mut src = vec![0.0; (h + 2) * M];
+                src[M..(h + 1) * M].copy_from_slice(&initial[rows.start * M..rows.end * M]);
+                let mut dst = src.clone();
+
+                for _step in 0..steps {
+                    // 1. 自分の端の行を隣へ送る（先に送るので、互いに相手の行を待っても止まらない）
+                    if let Some(tx) = links.to_up.as_mut() {
+                        tx.send_row(&src[M..2 * M]);
+                    }
+                    if let Some(tx) = links.to_down.as_mut() {
+                        tx.send_row(&src[h * M..(h + 1) * M]);
+                    }
+
+                    // 2. 隣から届いた行をゴースト行に受け取る
+                    if let Some(rx) = links.from_up.as_mut() {
+                        rx.recv_row(&mut src[..M]);
+                    }
+                    if let Some(rx) = links.from_down.as_mut() {
+                        rx.recv_row(&mut src[(h + 1) * M..]);
+                    }
+
+                    // 3. 担当行の計算
+                    update_band(&src, &mut dst, rows.start, h);
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+
+                *result.lock().unwrap() = src[M..(h + 1) * M].to_vec();
+            });
+        }
+    });
+
+    for (rows, result) in bands.iter().zip(results) {
+        a.data[rows.start * M..rows.end * M].copy_from_slice(&result.into_inner().unwrap());
+    }
+}
+
+// 専用バッファ（先頭と末尾にゴースト行）の担当行を更新する。外周の行と列は計算しない
+fn update_band(src: &[f64], dst: &mut [f64], first_row: usize, h: usize) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+
+    for l in 1..=h {
+        let i = first_row + l - 1;
+        if i == 0 || i == N - 1 {
+            continue;
+        }
+        for j in 1..M - 1 {
+            let idx = l * M + j;
+            let lap_x = src[idx - M] + src[idx + M] - 2.0 * src[idx];
+            let lap_y = src[idx - 1] + src[idx + 1] - 2.0 * src[idx];
+            dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
+        }
+    }
+
+    if (first_row..first_row + h).contains(&(N / 2)) {
+        dst[(N / 2 - first_row + 1) * M + M / 2] = 100.0;
+    }
+}
@@ -0,0 +1,263 @@
+use rayon::prelude::*;
+use crate::grid::{is_dirichlet, Grid, ALPHA, DT, DX, DY, N, M};
+
+/*
+  Crank–Nicolson 陰解法
+
+  (I - r/2·L) u^{n+1} = (I + r/2·L) u^n      (r·L = α·Δt·(δx²/Δx² + δy²/Δy²)、5点ラプラシアン)
+
+  陽解法と違い Δt に安定条件がないため、大きな時間刻みを取れる。
+  その代わり各ステップで連立一次方程式を解く必要があり、内部反復
+  （Jacobi反復 または 共役勾配法）で許容誤差まで収束させる。
+
+  境界（外周）と熱源は固定値（ディリクレ条件）として扱い、未知数から除外する。
+  内部反復の各スイープは行単位でRayonに分配する。
+*/
+
+/// 各ステップの連立方程式を解く内部反復法
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InnerSolver {
+    Jacobi,
+    ConjugateGradient,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CrankNicolsonConfig {
+    pub dt: f64,               // 時間刻み（安定条件に縛られない）
+    pub solver: InnerSolver,
+    pub tolerance: f64,        // 残差の最大値ノルムがこれ以下になったら収束
+    pub max_iterations: usize, // 1ステップあたりの内部反復の上限
+}
+
+impl Default for CrankNicolsonConfig {
+    fn default() -> Self {
+        CrankNicolsonConfig {
+            dt: DT,
+            solver: InnerSolver::ConjugateGradient,
+            tolerance: 1e-8,
+            max_iterations: 1000,
+        }
+    }
+}
+
+/// 精度とコストの比較用に返す統計情報
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CrankNicolsonStats {
+    pub inner_iterations: usize, // 全ステップの内部反復回数の合計
+    pub max_residual: f64,       // 各ステップ終了時の残差の最大値
+}
+
+pub fn crank_nicolson(
+    a: &mut Grid,
+    b: &mut Grid,
+    steps: usize,
+    config: &CrankNicolsonConfig,
+) -> CrankNicolsonStats {
+    let h = HalfFactors::new(config.dt);
+
+    let mut stats = CrankNicolsonStats::default();
+
+    // 作業バッファはステップ間で使い回す
+    let mut rhs = vec![0.0; N * M];
+    let mut work = [vec![0.0; N * M], vec![0.0; N * M], vec![0.0; N * M]];
+
+    for _ in 0..steps {
+        build_rhs(&a.data, &mut rhs, h);
+
+        // 初期推定値は前ステップの解（固定セルの値もここで引き継がれる）
+        b.data.copy_from_slice(&a.data);
+
+        let (iterations, residual) = match config.solver {
+            InnerSolver::Jacobi => {
+                solve_jacobi(&mut b.data, &rhs, &mut work[0], h, config)
+            }
+            InnerSolver::ConjugateGradient => {
+                solve_cg(&mut b.data, &rhs, &mut work, h, config)
+            }
+        };
+        stats.inner_iterations += iterations;
+        stats.max_residual = stats.max_residual.max(residual);
+
+        // single.rs と同様、入れ替えにより最新の解は常に a 側に来る
+        std::mem::swap(&mut a.data, &mut b.data);
+    }
+
+    stats
+}
+
+// r/2 を方向ごとに分けた係数と、行列 (I - r/2·L) の対角成分
+#[derive(Clone, Copy)]
+struct HalfFactors {
+    x: f64,
+    y: f64,
+    diag: f64,
+}
+
+impl HalfFactors {
+    fn new(dt: f64) -> Self {
+        let x = 0.5 * ALPHA * dt / (DX * DX);
+        let y = 0.5 * ALPHA * dt / (DY * DY);
+        HalfFactors { x, y, diag: 1.0 + 2.0 * (x + y) }
+    }
+
+    // 方向ごとの重みを掛けた隣接4点の和
+    #[inline(always)]
+    fn neighbor_sum(&self, x: &[f64], idx: usize) -> f64 {
+        self.x * (x[idx - M] + x[idx + M]) + self.y * (x[idx - 1] + x[idx + 1])
+    }
+}
+
+// 右辺 (I + r/2·L) u^n。固定セルには値をそのまま入れておく
+fn build_rhs(u: &[f64], rhs: &mut [f64], h: HalfFactors) {
+    rhs.par_chunks_mut(M).enumerate().for_each(|(i, rhs_row)| {
+        for j in 0..M {
+            let idx = i * M + j;
+            rhs_row[j] = if is_dirichlet(i, j) {
+                u[idx]
+            } else {
+                u[idx] + (h.neighbor_sum(u, idx) - (h.diag - 1.0) * u[idx])
+            };
+        }
+    });
+}
+
+// Jacobi反復: x_new = (rhs + Σ r/2·隣接) / diag
+// 更新量 δ は更新前の反復値の残差/diag に等しいため、収束判定に追加の走査は不要
+fn solve_jacobi(
+    x: &mut [f64],
+    rhs: &[f64],
+    scratch: &mut [f64],
+    h: HalfFactors,
+    config: &CrankNicolsonConfig,
+) -> (usize, f64) {
+    // 固定セルはスワップ後も値が残るよう、あらかじめ揃えておく
+    scratch.copy_from_slice(x);
+
+    let mut src: &mut [f64] = x;
+    let mut dst: &mut [f64] = scratch;
+    let mut iterations = 0;
+    let mut residual = f64::INFINITY;
+
+    while iterations < config.max_iterations {
+        let src_ref: &[f64] = src;
+        residual = dst
+            .par_chunks_mut(M)
+            .enumerate()
+            .map(|(i, dst_row)| {
+                let mut row_max: f64 = 0.0;
+                if i == 0 || i == N - 1 {
+                    return row_max;
+                }
+                for j in 1..M - 1 {
+                    if is_dirichlet(i, j) {
+                        continue;
+                    }
+                    let idx = i * M + j;
+                    let new = (rhs[idx] + h.neighbor_sum(src_ref, idx)) / h.diag;
+                    row_max = row_max.max((new - src_ref[idx]).abs());
+                    dst_row[j] = new;
+                }
+                row_max
+            })
+            .reduce(|| 0.0, f64::max)
+            * h.diag;
+
+        std::mem::swap(&mut src, &mut dst);
+        iterations += 1;
+
+        if residual <= config.tolerance {
+            break;
+        }
+    }
+
+    // 最新の反復値が scratch 側にある場合は x に書き戻す
+    if iterations % 2 == 1 {
+        dst.copy_from_slice(src);
+    }
+
+    (iterations, residual)
+}
+
+// 共役勾配法。固定セルは未知数から除外し、方向ベクトル p を常に0にしておくことで
+// 行列 (I - r/2·L) を対称正定値のまま扱う
+fn solve_cg(
+    x: &mut [f64],
+    rhs: &[f64],
+    work: &mut [Vec<f64>; 3],
+    h: HalfFactors,
+    config: &CrankNicolsonConfig,
+) -> (usize, f64) {
+    let [r, p, ap] = work;
+
+    // r = rhs - A·x （固定セルの値は隣接項として右辺側に含まれる）
+    r.par_chunks_mut(M).enumerate().for_each(|(i, r_row)| {
+        for j in 0..M {
+            let idx = i * M + j;
+            r_row[j] = if is_dirichlet(i, j) {
+                0.0
+            } else {
+                rhs[idx] - (h.diag * x[idx] - h.neighbor_sum(x, idx))
+            };
+        }
+    });
+    p.copy_from_slice(r);
+
+    let mut rr = dot(r, r);
+    let mut residual = max_abs(r);
+    let mut iterations = 0;
+
+    while residual > config.tolerance && iterations < config.max_iterations {
+        // ap = A·p
+        {
+            let p_ref: &[f64] = p;
+            ap.par_chunks_mut(M).enumerate().for_each(|(i, ap_row)| {
+                for j in 0..M {
+                    let idx = i * M + j;
+                    ap_row[j] = if is_dirichlet(i, j) {
+                        0.0
+                    } else {
+                        h.diag * p_ref[idx] - h.neighbor_sum(p_ref, idx)
+                    };
+                }
+            });
+        }
+
+        let step = rr / dot(p, ap);
+
+        x.par_iter_mut()
+            .zip(p.par_iter())
+            .for_each(|(xv, pv)| *xv += step * pv);
+        r.par_iter_mut()
+            .zip(ap.par_iter())
+            .for_each(|(rv, apv)| *rv -= step * apv);
+
+        let rr_new = dot(r, r);
+        let beta = rr_new / rr;
+        rr = rr_new;
+
+        p.par_iter_mut()
+            .zip(r.par_iter())
+            .for_each(|(pv, rv)| *pv = rv + beta * *pv);
+
+        residual = max_abs(r);
+        iterations += 1;
+    }
+
+    (iterations, residual)
+}
+
+// 行ごとの部分和を順番に足し合わせることで、スレッド数によらず結果を決定的にする
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.par_chunks(M)
+        .zip(y.par_chunks(M))
+        .map(|(xr, yr)| xr.iter().zip(yr).map(|(a, b)| a * b).sum::<f64>())
+        .collect::<Vec<f64>>()
+        .iter()
+        .sum()
+}
+
+fn max_abs(x: &[f64]) -> f64 {
+    x.par_chunks(M)
+        .map(|row| row.iter().fold(0.0_f64, |acc, v| acc.max(v.abs())))
+        .reduce(|| 0.0, f64::max)
+}
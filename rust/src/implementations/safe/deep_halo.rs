@@ -0,0 +1,153 @@
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use crate::grid::{band_ranges, Grid, ALPHA, DT, DX, DY, N, M};
+use crate::affinity;
+
+/*
+  深いハローによる通信削減型の帯分割
+
+  barrier_parallel / atomic_counter は毎ステップ1行のゴースト行を交換して同期する。
+  ここでは各帯が上下 depth 行のゴースト行を持ち、交換と同期は depth ステップに1回だけ行う。
+  交換の間の depth ステップは、ゴースト行も含めて各帯が自分のバッファで計算する（隣の帯と重複する計算）。
+  ステップ s（ブロック内で 0 始まり）で正しく計算できるのは、バッファの端から s+1 行目より内側なので、
+  計算する範囲を1ステップごとに1行ずつ狭め、depth ステップ後に担当行がちょうど正しくなるようにする。
+
+  交換バッファはブロックの偶奇で2組を使い分ける。速いスレッドが次のブロックで書き出しても、
+  遅いスレッドが読んでいる前のブロックの組は上書きされないため、バリアはブロックごとに1回で済む。
+  depth を増やすと同期回数は 1/depth になり、重複計算は帯1つあたり約 2·depth² 行ぶん増える。
+*/
+
+// ゴースト行の計算は隣の帯と重複するため、共有の格子には書けない。各帯は上下 depth 行を含む自分のバッファで
+// depth ステップ進めるので、2枚目のグリッド _b は使わない
+pub fn deep_halo_barrier(a: &mut Grid, _b: &mut Grid, steps: usize, num_threads: usize, depth: usize) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let depth = depth.clamp(1, N);
+    // 各帯はハローの深さ以上の行数が必要
+    let bands = band_ranges(N, num_threads.clamp(1, N / depth));
+    let count = bands.len();
+
+    let barrier = Arc::new(Barrier::new(count));
+    let halos = Arc::new([HaloExchange::new(count, depth), HaloExchange::new(count, depth)]);
+
+    // 各スレッドの結果（担当行）を最後に a に書き戻す
+    let results: Vec<Mutex<Vec<f64>>> = bands.iter().map(|_| Mutex::new(Vec::new())).collect();
+    let initial: &[f64] = &a.data;
+
+    thread::scope(|scope| {
+        for (id, rows) in bands.iter().cloned().enumerate() {
+            let barrier = barrier.clone();
+            let halos = halos.clone();
+            let result = &results[id];
+
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let w = depth;
+                let local_rows = rows.len();
+                let total = local_rows + 2 * w;
+
+                // [ゴースト w 行][担当 local_rows 行][ゴースト w 行]
+                let mut src = vec![0.0; total * M];
+                src[w * M..(w + local_rows) * M].copy_from_slice(&initial[rows.start * M..rows.end * M]);
+                let mut dst = src.clone();
+
+                // バッファの l 行目が全体の何行目か（上の帯が無い部分は None）
+                let global_row = |l: usize| (rows.start + l).checked_sub(w);
+                // 熱源はゴースト行に含まれる場合も固定する（重複計算の結果を担当の帯と揃えるため）
+                let source_row = (N / 2 + w).checked_sub(rows.start).filter(|&l| l < total);
+
+                let mut done = 0;
+                let mut block = 0;
+                while done < steps {
+                    let d = w.min(steps - done);
+                    let halo = &halos[block % 2];
+
+                    // 1. 自分の先頭・末尾 w 行を書き出す
+                    halo.publish(id, &src[w * M..(w + local_rows) * M]);
+
+                    // バリア: 全員が書き出すのを待つ（ブロックに1回）
+                    barrier.wait();
+
+                    // 2. 隣接する帯の w 行をゴースト行に取り込む（計算しない外周行も含めて dst を揃えておく）
+                    {
+                        let (top, rest) = src.split_at_mut(w * M);
+                        let bottom = &mut rest[local_rows * M..];
+                        halo.receive(id, top, bottom);
+                    }
+                    dst.copy_from_slice(&src);
+
+                    // 3. 同期せずに d ステップ進める。計算する範囲は1ステップごとに上下1行ずつ狭まる
+                    for s in 0..d {
+                        for l in (s + 1)..(total - 1 - s) {
+                            // 外周と、上下の帯が無い部分は計算しない
+                            if !global_row(l).is_some_and(|i| (1..N - 1).contains(&i)) {
+                                continue;
+                            }
+                            for j in 1..M - 1 {
+                                let idx = l * M + j;
+                                let lap_x = src[idx - M] + src[idx + M] - 2.0 * src[idx];
+                                let lap_y = src[idx - 1] + src[idx + 1] - 2.0 * src[idx];
+                                dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
+                            }
+                        }
+                        if let Some(row) = source_row {
+                            dst[row * M + M / 2] = 100.0;
+                        }
+
+                        std::mem::swap(&mut src, &mut dst);
+                    }
+
+                    done += d;
+                    block += 1;
+                }
+
+                *result.lock().unwrap() = src[w * M..(w + local_rows) * M].to_vec();
+            });
+        }
+    });
+
+    for (rows, result) in bands.iter().zip(results) {
+        a.data[rows.start * M..rows.end * M].copy_from_slice(&result.into_inner().unwrap());
+    }
+}
+
+/// 帯の境界行の交換バッファ
+///
+/// 帯 id は先頭 width 行を first[id] に、末尾 width 行を last[id] に書き出し、
+/// 隣の帯はそれを自分のゴースト行にコピーする。
+pub struct HaloExchange {
+    width: usize,
+    first: Vec<Mutex<Vec<f64>>>,
+    last: Vec<Mutex<Vec<f64>>>,
+}
+
+impl HaloExchange {
+    pub fn new(bands: usize, width: usize) -> Self {
+        HaloExchange {
+            width,
+            first: (0..bands).map(|_| Mutex::new(vec![0.0; width * M])).collect(),
+            last: (0..bands).map(|_| Mutex::new(vec![0.0; width * M])).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// owned（帯が担当する行、width 行以上）の先頭・末尾 width 行を書き出す
+    pub fn publish(&self, id: usize, owned: &[f64]) {
+        let n = self.width * M;
+        self.first[id].lock().unwrap().copy_from_slice(&owned[..n]);
+        self.last[id].lock().unwrap().copy_from_slice(&owned[owned.len() - n..]);
+    }
+
+    /// 隣接する帯の境界行をゴースト行にコピーする。端の帯では外側のゴースト行はそのまま
+    pub fn receive(&self, id: usize, ghost_above: &mut [f64], ghost_below: &mut [f64]) {
+        if id > 0 {
+            ghost_above.copy_from_slice(&self.last[id - 1].lock().unwrap());
+        }
+        if id + 1 < self.first.len() {
+            ghost_below.copy_from_slice(&self.first[id + 1].lock().unwrap());
+        }
+    }
+}
@@ -1,4 +1,14 @@
 pub mod single;
 pub mod atomic_counter;
 pub mod barrier;
-pub mod rayon;
\ No newline at end of file
+pub mod rayon;
+pub mod crank_nicolson;
+pub mod adi;
+pub mod runge_kutta;
+pub mod three_d;
+pub mod temporal;
+pub mod deep_halo;
+pub mod block2d;
+pub mod channel;
+pub mod scoped_bands;
+pub mod pooled;
@@ -0,0 +1,67 @@
+use std::ops::Range;
+use crate::grid::{band_ranges, Grid, N};
+use crate::halo::{split_bands, HaloSchedule};
+use crate::implementations::safe::atomic_counter::atomic_counter::CounterExchange;
+use crate::implementations::safe::barrier::barrier_parallel::BarrierExchange;
+use crate::pool::{slots, take, BandPool};
+use crate::stencil::FivePoint;
+use crate::sync::{StepCounter, WaitStrategy};
+
+/*
+  常駐ワーカープールを使う帯分割ソルバー（safe 版）
+
+  barrier_parallel / atomic_counter と同じく、境界行を AtomicRow で交換する同期方法を BandPool のスレッドで実行する。
+  格子は split_bands で帯ごとの可変スライスに分けてワーカーに渡すため、unsafe は使わない。
+  SharedGrid で隣の帯を直接読む版は unsafe_impl::pooled にある。
+*/
+
+// 帯 id とその帯の (a, b) の行を受け取って計算する
+type BandJob<'a> = dyn Fn(usize, &mut [f64], &mut [f64]) + Sync + 'a;
+
+pub struct PooledSolver {
+    pool: BandPool,
+    bands: Vec<Range<usize>>,
+}
+
+impl PooledSolver {
+    pub fn new(num_threads: usize) -> Self {
+        // カウンタ同期は、各帯に上下の境界行と内部行が要る
+        let bands = band_ranges(N, num_threads.clamp(1, N / 2));
+        PooledSolver { pool: BandPool::new(bands.len()), bands }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.threads()
+    }
+
+    /// barrier_parallel と同じく、境界行を AtomicRow で交換し、Barrier で区切る
+    pub fn barrier(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, steps: usize) {
+        let exchange = BarrierExchange::new(&self.bands, 1);
+        self.run_split(grid_a, grid_b, &|id, a_band, b_band| exchange.run_band(id, a_band, b_band, &FivePoint, steps));
+        if !steps.is_multiple_of(2) {
+            grid_a.data.copy_from_slice(&grid_b.data);
+        }
+    }
+
+    /// atomic_counter と同じく、境界行を AtomicRow で交換し、隣接する帯のカウンタだけを待つ
+    pub fn atomic_counter(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, steps: usize) {
+        let exchange = CounterExchange::new(&self.bands, 1, || StepCounter::new(WaitStrategy::default()));
+        self.run_split(grid_a, grid_b, &|id, a_band, b_band| {
+            exchange.run_band(id, a_band, b_band, &FivePoint, steps, HaloSchedule::WaitFirst, None)
+        });
+        if !steps.is_multiple_of(2) {
+            grid_a.data.copy_from_slice(&grid_b.data);
+        }
+    }
+
+    // 2枚の格子を帯ごとの可変スライスに分け、ワーカー id に帯 id の組を渡して band を呼ぶ
+    fn run_split(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, band: &BandJob<'_>) {
+        let a_bands = split_bands(&mut grid_a.data, &self.bands);
+        let b_bands = split_bands(&mut grid_b.data, &self.bands);
+        let pairs = slots(a_bands.into_iter().zip(b_bands));
+        self.pool.run_synchronized(&|id| {
+            let (a_band, b_band) = take(&pairs, id);
+            band(id, a_band, b_band);
+        });
+    }
+}
@@ -1,46 +1,138 @@
 use rayon::prelude::*;
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
+use crate::diffusivity::Diffusivity;
+use crate::grid::{Domain, Grid, ALPHA, DT, DX, DY, N, M};
+use crate::simd::{update_row, SimdKernel, SimdLevel};
+use crate::stencil::{FivePoint, Stencil};
+use crate::tiling::TileConfig;
 
 //書き込み先を完全に分離することで、ロック不要の並列化を実現する
 pub fn rayon_parallel(a: &mut Grid, b: &mut Grid, steps: usize) {
-    let factor = ALPHA * DT / (DX * DX);
+    rayon_parallel_stencil(a, b, &FivePoint, steps);
+}
+
+// 可変拡散係数版。行の分配方法は rayon_parallel と同じ
+pub fn rayon_parallel_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize) {
+    rayon_parallel_stencil(a, b, coef, steps);
+}
+
+// 本体。セルの更新式は stencil で選ぶ
+pub fn rayon_parallel_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize) {
+    let r = S::RADIUS;
 
     //ここでは src（読み取り元）と dst（書き込み先）という2つのスライスを用意
     let mut src = &mut a.data[..];
     let mut dst = &mut b.data[..];
 
     for _step in 0..steps {
-        // 境界行（最上行・最下行）は変化しない（Neumann境界条件）
+        // 外周 r 行（最上行・最下行）は変化しない
         //「計算できない端っこの値を、最新のバッファ（dst）にも正しく引き継ぐ」ために不可欠な処理
-        dst[0..M].copy_from_slice(&src[0..M]); //一番上の行
-        dst[(N - 1) * M..N * M].copy_from_slice(&src[(N - 1) * M..N * M]); //一番下の行
+        dst[..r * M].copy_from_slice(&src[..r * M]); //上端の行
+        dst[(N - r) * M..].copy_from_slice(&src[(N - r) * M..]); //下端の行
 
-        // 内部領域（1行目 ～ N-2行目）のみをスライスとして切り出す。この部分が並列計算の対象
-        let interior_dst = &mut dst[M..(N - 1) * M];
+        let src_ref: &[f64] = src;
 
-        
         //書き込み先のグリッドを「行」単位で分割し、複数のCPUコア（スレッド）に分配して同時に計算
         //各スレッドは異なる行（dst_row）に書き込むため、ロック（Mutexなど）を使わずに安全かつ高速に並列処理が可能です。
-        interior_dst
+        dst[r * M..(N - r) * M]
             .par_chunks_mut(M) // 行ごとにスライスを分割
             .enumerate() //各行ごとにインデックスを付与
-            .for_each(|(r, dst_row)| {
+            .for_each(|(row, dst_row)| {
                 //以下各スレッドで実行
 
-                // rは切り出した内部領域の中での行番号 (0始まり)
-                // 実際のgrid上の行は r + 1
+                // row は切り出した内部領域の中での行番号 (0始まり)。実際のgrid上の行は row + r
+                let i = row + r;
+                stencil.apply_row(i, |k| &src_ref[k * M..(k + 1) * M], dst_row);
+
+                // 左右の外周 r 列も引き継ぐ
+                let cur = &src_ref[i * M..(i + 1) * M];
+                dst_row[..r].copy_from_slice(&cur[..r]);
+                dst_row[M - r..].copy_from_slice(&cur[M - r..]);
+            });
+
+        dst[(N / 2) * M + M / 2] = 100.0;
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if !steps.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// 各行の計算を std::arch の行カーネル（simd.rs）で行う版
+pub fn rayon_simd(a: &mut Grid, b: &mut Grid, steps: usize, level: SimdLevel) {
+    let kernel = SimdKernel::require(level);
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    for _step in 0..steps {
+        dst[0..M].copy_from_slice(&src[0..M]);
+        dst[(N - 1) * M..N * M].copy_from_slice(&src[(N - 1) * M..N * M]);
+
+        let src_ref: &[f64] = src;
+        dst[M..(N - 1) * M]
+            .par_chunks_mut(M)
+            .enumerate()
+            .for_each(|(r, dst_row)| {
                 let i = r + 1;
+                update_row(kernel, &src_ref[(i - 1) * M..i * M], &src_ref[i * M..(i + 1) * M],
+                           &src_ref[(i + 1) * M..(i + 2) * M], dst_row, factor_x, factor_y);
+                dst_row[0] = src_ref[i * M];
+                dst_row[M - 1] = src_ref[i * M + M - 1];
+            });
+
+        dst[(N / 2) * M + M / 2] = 100.0;
+
+        std::mem::swap(&mut src, &mut dst);
+    }
 
-                for j in 1..M - 1 {
-                    let idx = i * M + j;
-                    let laplacian = src[idx - M] + src[idx + M] +
-                                    src[idx - 1] + src[idx + 1] -
-                                    4.0 * src[idx];
-                    dst_row[j] = src[idx] + factor * laplacian;
+    if !steps.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// 空間タイリング版: tiles.height 行の帯をRayonに分配し、各帯の中を tiles.width 列ずつ処理する
+// （書き込み先を帯単位で分けるため、安全なスライス分割のままタイルを扱える）
+pub fn rayon_tiled(a: &mut Grid, b: &mut Grid, steps: usize, tiles: &TileConfig) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let height = tiles.height.max(1);
+    let col_blocks = tiles.col_blocks();
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    for _step in 0..steps {
+        dst[0..M].copy_from_slice(&src[0..M]);
+        dst[(N - 1) * M..N * M].copy_from_slice(&src[(N - 1) * M..N * M]);
+
+        let src_ref: &[f64] = src;
+        dst[M..(N - 1) * M]
+            .par_chunks_mut(height * M)
+            .enumerate()
+            .for_each(|(block, dst_block)| {
+                let i0 = 1 + block * height;
+                let rows = dst_block.len() / M;
+
+                for cols in &col_blocks {
+                    for r in 0..rows {
+                        let i = i0 + r;
+                        for j in cols.clone() {
+                            let idx = i * M + j;
+                            let lap_x = src_ref[idx - M] + src_ref[idx + M] - 2.0 * src_ref[idx];
+                            let lap_y = src_ref[idx - 1] + src_ref[idx + 1] - 2.0 * src_ref[idx];
+                            dst_block[r * M + j] = src_ref[idx] + (factor_x * lap_x + factor_y * lap_y);
+                        }
+                    }
                 }
 
-                dst_row[0] = src[i * M];
-                dst_row[M - 1] = src[i * M + M - 1];
+                for r in 0..rows {
+                    dst_block[r * M] = src_ref[(i0 + r) * M];
+                    dst_block[r * M + M - 1] = src_ref[(i0 + r) * M + M - 1];
+                }
             });
 
         dst[(N / 2) * M + M / 2] = 100.0;
@@ -48,7 +140,33 @@ pub fn rayon_parallel(a: &mut Grid, b: &mut Grid, steps: usize) {
         std::mem::swap(&mut src, &mut dst);
     }
 
-    if steps % 2 != 0 {
+    if !steps.is_multiple_of(2) {
         a.data.copy_from_slice(&b.data);
     }
-}
\ No newline at end of file
+}
+
+// Rayonによる行並列のラプラシアン評価（Runge–Kutta法の各ステージから使う）
+// out = α·Δt·∇²src（方向ごとに factor_x, factor_y を掛ける）。外周と熱源は固定値なので 0 とする
+pub fn laplacian_rayon(src: &[f64], out: &mut [f64], domain: &Domain) {
+    let (rows, cols) = (domain.rows, domain.cols);
+    let (factor_x, factor_y) = (domain.factor_x, domain.factor_y);
+
+    out.par_chunks_mut(cols).enumerate().for_each(|(i, out_row)| {
+        if i == 0 || i == rows - 1 {
+            out_row.fill(0.0);
+            return;
+        }
+        for j in 1..cols - 1 {
+            let idx = i * cols + j;
+            let lap_x = src[idx - cols] + src[idx + cols] - 2.0 * src[idx];
+            let lap_y = src[idx - 1] + src[idx + 1] - 2.0 * src[idx];
+            out_row[j] = factor_x * lap_x + factor_y * lap_y;
+        }
+        out_row[0] = 0.0;
+        out_row[cols - 1] = 0.0;
+    });
+
+    if let Some(source) = domain.heat_source {
+        out[source] = 0.0;
+    }
+}
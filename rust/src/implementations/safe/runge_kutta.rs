@@ -0,0 +1,190 @@
+use rayon::prelude::*;
+use crate::grid::{explicit_dt_limit, Domain, Grid, DT};
+use crate::implementations::safe::rayon::rayon::laplacian_rayon;
+
+/*
+  高次の陽的時間積分（Runge–Kutta法）
+
+  F(u) = Δt·α∇²u（laplacian_rayon で行並列に評価）として、
+    前進オイラー : u' = u + F(u)
+    Heun (RK2)   : u' = u + (F(u) + F(u + F(u))) / 2
+    SSP-RK3      : Shu–Osher形式の3段3次
+    古典的RK4    : 4段4次
+  を切り替えて使う。段数が増えるほどステージ用のバッファが増え、
+  1ステップあたりのメモリ転送量も増える。
+
+  前進オイラーは rayon_parallel と同じ演算順序になるため、結果はビット単位で一致する。
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    ForwardEuler,
+    Heun,
+    SspRk3,
+    Rk4,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 4] = [
+        Integrator::ForwardEuler,
+        Integrator::Heun,
+        Integrator::SspRk3,
+        Integrator::Rk4,
+    ];
+
+    /// 1ステップあたりのラプラシアン評価回数
+    pub fn stages(&self) -> usize {
+        match self {
+            Integrator::ForwardEuler => 1,
+            Integrator::Heun => 2,
+            Integrator::SspRk3 => 3,
+            Integrator::Rk4 => 4,
+        }
+    }
+
+    /// 時間方向の精度の次数
+    pub fn order(&self) -> usize {
+        self.stages()
+    }
+
+    /// u と出力バッファ以外に必要な作業バッファの数
+    pub fn extra_buffers(&self) -> usize {
+        match self {
+            Integrator::ForwardEuler => 0,
+            Integrator::Heun | Integrator::SspRk3 => 1,
+            Integrator::Rk4 => 2,
+        }
+    }
+
+    /// 安定な時間刻みの上限。負の実軸上の安定領域の幅を前進オイラー（= 2）と比べた倍率を掛ける
+    pub fn dt_limit(&self) -> f64 {
+        let ratio = match self {
+            Integrator::ForwardEuler | Integrator::Heun => 1.0,
+            Integrator::SspRk3 => 2.5127 / 2.0,
+            Integrator::Rk4 => 2.7853 / 2.0,
+        };
+        explicit_dt_limit() * ratio
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Integrator::ForwardEuler => "Forward Euler",
+            Integrator::Heun => "Heun (RK2)",
+            Integrator::SspRk3 => "SSP-RK3",
+            Integrator::Rk4 => "RK4",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RungeKuttaConfig {
+    pub dt: f64,
+    pub integrator: Integrator,
+}
+
+impl Default for RungeKuttaConfig {
+    fn default() -> Self {
+        RungeKuttaConfig {
+            dt: DT,
+            integrator: Integrator::Rk4,
+        }
+    }
+}
+
+pub fn runge_kutta(a: &mut Grid, b: &mut Grid, steps: usize, config: &RungeKuttaConfig) {
+    let domain = Domain::standard(config.dt);
+    integrate(&mut a.data, &mut b.data, &domain, config.integrator, steps);
+}
+
+/// 任意サイズの領域で steps ステップ進める。結果は u に入る
+///
+/// out は出力用の2枚目のバッファ（Grid の b に相当）。追加のステージ用バッファはここで確保する。
+pub fn integrate(u: &mut [f64], out: &mut [f64], domain: &Domain, integrator: Integrator, steps: usize) {
+    let len = domain.len();
+    let mut stage = vec![0.0; if integrator.extra_buffers() >= 1 { len } else { 0 }];
+    let mut k = vec![0.0; if integrator.extra_buffers() >= 2 { len } else { 0 }];
+
+    // rayon.rs と同じく参照を入れ替えてダブルバッファリングする
+    let mut u_ref: &mut [f64] = &mut *u;
+    let mut out_ref: &mut [f64] = &mut *out;
+
+    for _ in 0..steps {
+        let (u, out) = (&*u_ref, &mut *out_ref);
+        match integrator {
+            Integrator::ForwardEuler => {
+                // out = u + F(u)
+                laplacian_rayon(u, out, domain);
+                combine(out, u, |f, u0| u0 + f);
+            }
+            Integrator::Heun => {
+                // stage = u + F(u)
+                laplacian_rayon(u, &mut stage, domain);
+                combine(&mut stage, u, |f, u0| u0 + f);
+                // out = (u + (stage + F(stage))) / 2
+                laplacian_rayon(&stage, out, domain);
+                combine2(out, u, &stage, |f, u0, s| 0.5 * (u0 + (s + f)));
+            }
+            Integrator::SspRk3 => {
+                // u1 = u + F(u)
+                laplacian_rayon(u, &mut stage, domain);
+                combine(&mut stage, u, |f, u0| u0 + f);
+                // u2 = 3/4 u + 1/4 (u1 + F(u1))
+                laplacian_rayon(&stage, out, domain);
+                combine2(out, u, &stage, |f, u0, u1| 0.75 * u0 + 0.25 * (u1 + f));
+                // u' = 1/3 u + 2/3 (u2 + F(u2))
+                laplacian_rayon(out, &mut stage, domain);
+                combine2(out, u, &stage, |u2, u0, f| u0 / 3.0 + 2.0 / 3.0 * (u2 + f));
+            }
+            Integrator::Rk4 => {
+                // out に u + (k1 + 2k2 + 2k3 + k4) / 6 を蓄積し、stage に次の段の入力を作る
+                laplacian_rayon(u, &mut k, domain);
+                rk4_stage(out, &mut stage, u, &k, None, 0.5);
+                laplacian_rayon(&stage, &mut k, domain);
+                rk4_stage(out, &mut stage, u, &k, Some(1.0 / 3.0), 0.5);
+                laplacian_rayon(&stage, &mut k, domain);
+                rk4_stage(out, &mut stage, u, &k, Some(1.0 / 3.0), 1.0);
+                laplacian_rayon(&stage, &mut k, domain);
+                out.par_iter_mut()
+                    .zip(k.par_iter())
+                    .for_each(|(o, k4)| *o += k4 / 6.0);
+            }
+        }
+
+        std::mem::swap(&mut u_ref, &mut out_ref);
+    }
+
+    if steps % 2 == 1 {
+        u.copy_from_slice(out);
+    }
+}
+
+// dst = op(dst, src)。dst には F の値が入っている
+#[inline(always)]
+fn combine(dst: &mut [f64], src: &[f64], op: impl Fn(f64, f64) -> f64 + Sync) {
+    dst.par_iter_mut()
+        .zip(src.par_iter())
+        .for_each(|(d, s)| *d = op(*d, *s));
+}
+
+// dst = op(dst, x, y)
+#[inline(always)]
+fn combine2(dst: &mut [f64], x: &[f64], y: &[f64], op: impl Fn(f64, f64, f64) -> f64 + Sync) {
+    dst.par_iter_mut()
+        .zip(x.par_iter().zip(y.par_iter()))
+        .for_each(|(d, (xv, yv))| *d = op(*d, *xv, *yv));
+}
+
+// RK4の中間段: acc += weight·k（weight が None なら acc = u + k/6 で初期化）、stage = u + shift·k
+#[inline(always)]
+fn rk4_stage(acc: &mut [f64], stage: &mut [f64], u: &[f64], k: &[f64], weight: Option<f64>, shift: f64) {
+    acc.par_iter_mut()
+        .zip(stage.par_iter_mut())
+        .zip(u.par_iter().zip(k.par_iter()))
+        .for_each(|((a, s), (u0, kv))| {
+            match weight {
+                Some(w) => *a += w * kv,
+                None => *a = u0 + kv / 6.0,
+            }
+            *s = u0 + shift * kv;
+        });
+}
@@ -0,0 +1,77 @@
+use std::sync::Barrier;
+use std::thread;
+use crate::affinity;
+use crate::double_buffer::{BandedDoubleBuffer, StepView};
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+
+/*
+  Mutex も unsafe も使わない N 帯の並列実装（double_buffer.rs の BandedDoubleBuffer）
+
+  barrier_unsafe と同じく、スレッドは最初に1回だけ作り、1ステップごとに全スレッドで Barrier を待つ。
+  各ステップでは step_view で「読む側の自分と隣の帯（&）」と「書く側の自分の帯（&mut）」を借り、
+  計算が終わったら借用を返してからバリアを待つ。境界行のコピーは無く、隣の帯の行を直接読む。
+  バリアで区切られているため RwLock は常に待たずに取れるが、1ステップあたり帯ごとに最大4回のロック操作
+  （読み3つ・書き1つ。取得と解放のアトミック操作）が計算に加わる。barrier_unsafe と比べるときはこの分を含む。
+*/
+
+// 2枚目のグリッドの役割は BandedDoubleBuffer が持つため、_b は使わない
+// 格子と帯ごとのバッファの間のコピー（from_grid / store）を含む。含まない時間は scoped_bands_run で測る
+pub fn scoped_bands(a: &mut Grid, _b: &mut Grid, steps: usize, num_threads: usize) {
+    let buffers = BandedDoubleBuffer::from_grid(a, num_threads);
+    scoped_bands_run(&buffers, steps);
+    buffers.store(steps, a);
+}
+
+// 用意済みの buffers を steps ステップ進める（結果は buffers.store で取り出す）
+pub fn scoped_bands_run(buffers: &BandedDoubleBuffer, steps: usize) {
+    let barrier = Barrier::new(buffers.band_count());
+    let (buffers_ref, barrier) = (buffers, &barrier);
+
+    thread::scope(|scope| {
+        for id in 0..buffers.band_count() {
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                for step in 0..steps {
+                    {
+                        let mut view = buffers_ref.step_view(step, id);
+                        update_band(&mut view);
+                    }
+                    // 借用を返してから待つ（次のステップでは読み書きの向きが入れ替わる）
+                    barrier.wait();
+                }
+            });
+        }
+    });
+}
+
+// 帯の担当行を更新する（外周の行と列は計算しない）
+fn update_band(view: &mut StepView<'_>) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let rows = view.rows.clone();
+    let (above, src, below, dst) = view.parts();
+    let h = rows.len();
+
+    for l in 0..h {
+        let i = rows.start + l;
+        if i == 0 || i == N - 1 {
+            continue;
+        }
+
+        // 帯の先頭・最終行の隣は隣接する帯の行（外周の行以外では必ず隣の帯がある）
+        let up = if l == 0 { above.unwrap() } else { &src[(l - 1) * M..l * M] };
+        let down = if l + 1 == h { below.unwrap() } else { &src[(l + 1) * M..(l + 2) * M] };
+        let curr = &src[l * M..(l + 1) * M];
+        let out = &mut dst[l * M..(l + 1) * M];
+
+        for j in 1..M - 1 {
+            let lap_x = up[j] + down[j] - 2.0 * curr[j];
+            let lap_y = curr[j - 1] + curr[j + 1] - 2.0 * curr[j];
+            out[j] = curr[j] + (factor_x * lap_x + factor_y * lap_y);
+        }
+    }
+
+    if rows.contains(&(N / 2)) {
+        dst[(N / 2 - rows.start) * M + M / 2] = 100.0;
+    }
+}
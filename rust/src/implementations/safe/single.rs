@@ -1,22 +1,81 @@
 use std::mem;
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
+use crate::diffusivity::Diffusivity;
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use crate::simd::{update_row, SimdKernel, SimdLevel};
+use crate::stencil::{FivePoint, Stencil};
+use crate::tiling::TileConfig;
 
 pub fn jacobi_step(a: &mut Grid, b: &mut Grid,steps:usize){
-    let factor = ALPHA * DT / (DX * DX);
+    jacobi_step_stencil(a, b, &FivePoint, steps);
+}
+
+// 可変拡散係数版: セルごとに面係数を読み込む
+pub fn jacobi_step_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize){
+    jacobi_step_stencil(a, b, coef, steps);
+}
+
+// 本体。セルの更新式は stencil で選ぶ（FivePoint なら jacobi_step、Diffusivity なら可変係数版）
+pub fn jacobi_step_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize){
+    let r = S::RADIUS;
+
+    for _ in 0..steps{
+
+        let src: &[f64] = &a.data;
+        for i in r..N-r{
+            stencil.apply_row(i, |k| &src[k * M..(k+1) * M], &mut b.data[i * M..(i+1) * M]);
+        }
+
+        // 熱源位置を固定温度に設定（最後に1回だけ）
+        b.data[(N/2) * M + M/2] = 100.0;
+
+        mem::swap(&mut a.data, &mut b.data);
+    }
+}
+
+// 内側の行ループを std::arch の行カーネル（simd.rs）に置き換えた版。level で命令セットを選ぶ
+pub fn jacobi_step_simd(a: &mut Grid, b: &mut Grid, steps: usize, level: SimdLevel){
+    // CPU の対応はここで1回だけ確認する（使えなければ panic）
+    let kernel = SimdKernel::require(level);
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
 
     for _ in 0..steps{
 
         for i in 1..N-1{
-            for j in 1..M-1{
-                let idx = i * M + j;
-                let laplacian = a.data[(i+1) * M + j] + a.data[(i-1) * M + j]
-                                + a.data[i * M + (j+1)] + a.data[i * M + (j-1)]
-                                - 4.0 * a.data[idx];
-                b.data[idx] = a.data[idx] + factor * laplacian;
+            update_row(kernel,
+                       &a.data[(i-1) * M..i * M], &a.data[i * M..(i+1) * M], &a.data[(i+1) * M..(i+2) * M],
+                       &mut b.data[i * M..(i+1) * M], factor_x, factor_y);
+        }
+
+        b.data[(N/2) * M + M/2] = 100.0;
+
+        mem::swap(&mut a.data, &mut b.data);
+    }
+}
+
+// 空間タイリング版: 内部領域を tiles.height 行 × tiles.width 列のタイルごとに計算する
+// 計算順序が変わるだけで各セルの式は jacobi_step と同じため、結果はビット単位で一致する
+pub fn jacobi_step_tiled(a: &mut Grid, b: &mut Grid, steps: usize, tiles: &TileConfig){
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let row_blocks = tiles.row_blocks();
+    let col_blocks = tiles.col_blocks();
+
+    for _ in 0..steps{
+
+        for rows in &row_blocks{
+            for cols in &col_blocks{
+                for i in rows.clone(){
+                    for j in cols.clone(){
+                        let idx = i * M + j;
+                        let lap_x = a.data[(i+1) * M + j] + a.data[(i-1) * M + j] - 2.0 * a.data[idx];
+                        let lap_y = a.data[i * M + (j+1)] + a.data[i * M + (j-1)] - 2.0 * a.data[idx];
+                        b.data[idx] = a.data[idx] + (factor_x * lap_x + factor_y * lap_y);
+                    }
+                }
             }
         }
 
-        // 熱源位置を固定温度に設定（最後に1回だけ）
         b.data[(N/2) * M + M/2] = 100.0;
 
         mem::swap(&mut a.data, &mut b.data);
@@ -0,0 +1,153 @@
+use rayon::prelude::*;
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+
+/*
+  時間方向のブロッキング（ウェーブフロント）
+
+  他の実装は1ステップごとに全体を走査して同期・バッファ交換するため、大きな格子ではメモリ帯域で律速する。
+  ここでは depth ステップ分をまとめて、行の「波面」に沿って進める。
+  波面 r では、ステップ t (1 ≤ t ≤ depth) の行 i = r - 2(t-1) を計算する。
+
+  2枚のバッファだけで済むよう、ステップごとに2行ずらしている（平行四辺形のタイル）。
+    - ステップ t の行 i は、ステップ t-1 の行 i-1..i+1 が波面 r-1 までに揃ってから計算される
+    - ステップ t の行 i で上書きするのはステップ t-2 の行 i で、それを読むステップ t-1 の行 i±1 は計算済み
+  波面1本で触る行は 2·depth+1 行程度なので、depth ステップ分をキャッシュに載せたまま計算できる。
+  各セルの式と入力は jacobi_step と同じなので、結果はビット単位で一致する。
+
+  Rayon版は帯ごとに上下 depth 行のハローを付けた専用バッファで、帯の中で depth ステップ進める
+  （ハロー部分は隣の帯と重複して計算するため、共有の格子の上では直接計算できない）。
+  専用バッファは最初に1回だけ確保し、ブロックごとに読み直すのはハローの行、書き戻すのは自分の行だけ。
+  同期とバッファ交換は depth ステップに1回になる。
+*/
+
+#[derive(Clone, Copy, Debug)]
+pub struct TemporalConfig {
+    pub depth: usize,       // 1回のブロックで進めるステップ数
+    pub band_height: usize, // Rayon版で1タスクが担当する行数
+}
+
+impl Default for TemporalConfig {
+    fn default() -> Self {
+        TemporalConfig { depth: 8, band_height: 64 }
+    }
+}
+
+impl TemporalConfig {
+    pub fn label(&self) -> String {
+        format!("depth={}, band={}", self.depth, self.band_height)
+    }
+}
+
+pub fn wavefront_single(a: &mut Grid, b: &mut Grid, steps: usize, config: &TemporalConfig) {
+    let depth = config.depth.max(1);
+
+    let mut done = 0;
+    while done < steps {
+        let d = depth.min(steps - done);
+        // 現在の値は done が偶数なら a、奇数なら b にある
+        if done.is_multiple_of(2) {
+            wavefront_block(&mut a.data[..], &mut b.data[..], 0, d);
+        } else {
+            wavefront_block(&mut b.data[..], &mut a.data[..], 0, d);
+        }
+        done += d;
+    }
+
+    if !steps.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+pub fn wavefront_rayon(a: &mut Grid, b: &mut Grid, steps: usize, config: &TemporalConfig) {
+    let depth = config.depth.max(1);
+    let height = config.band_height.max(1);
+
+    // 帯ごとの行範囲と、上下 depth 行のハローを付けた範囲（外周で打ち切る）
+    let bands: Vec<(usize, usize, usize, usize)> = (1..N - 1)
+        .step_by(height)
+        .map(|lo| {
+            let hi = (lo + height).min(N - 1);
+            (lo, hi, lo.saturating_sub(depth), (hi + depth).min(N))
+        })
+        .collect();
+
+    // 帯ごとの専用バッファ（現在の値, もう1枚）は最初に1回だけ確保し、ブロックをまたいで使い回す。
+    // 自分の行の値はブロックの後も現在の値のバッファに残るので、次のブロックで読み直すのはハローの行だけ
+    let mut scratch: Vec<(Vec<f64>, Vec<f64>)> = bands
+        .iter()
+        .map(|&(_, _, ext_lo, ext_hi)| {
+            let buf = a.data[ext_lo * M..ext_hi * M].to_vec();
+            (buf.clone(), buf)
+        })
+        .collect();
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    let mut done = 0;
+    let mut blocks: usize = 0;
+    while done < steps {
+        let d = depth.min(steps - done);
+
+        let src_ref: &[f64] = src;
+        dst[M..(N - 1) * M]
+            .par_chunks_mut(height * M)
+            .zip(scratch.par_iter_mut())
+            .zip(bands.par_iter())
+            .for_each(|((dst_band, (cur, other)), &(lo, hi, ext_lo, ext_hi))| {
+                // 隣の帯が前のブロックで書いたハローの行を取り込む
+                cur[..(lo - ext_lo) * M].copy_from_slice(&src_ref[ext_lo * M..lo * M]);
+                cur[(hi - ext_lo) * M..].copy_from_slice(&src_ref[hi * M..ext_hi * M]);
+
+                wavefront_block(cur, other, ext_lo, d);
+                if !d.is_multiple_of(2) {
+                    std::mem::swap(cur, other);
+                }
+
+                dst_band.copy_from_slice(&cur[(lo - ext_lo) * M..(hi - ext_lo) * M]);
+            });
+
+        std::mem::swap(&mut src, &mut dst);
+        done += d;
+        blocks += 1;
+    }
+
+    // バッファの交換はブロックごとに1回なので、ステップ数ではなくブロック数の偶奇で結果の位置が決まる
+    if !blocks.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// buf0 に時刻 0 の値を持つ行の並び（先頭は全体の row0 行目）を depth ステップ進める
+// 結果は depth が偶数なら buf0、奇数なら buf1 に入る。先頭と末尾の行は更新しないため、
+// それが外周でない場合は、端から t 行以内の値がステップ t で正しくなくなる（呼び出し側がハローで吸収する）
+fn wavefront_block(buf0: &mut [f64], buf1: &mut [f64], row0: usize, depth: usize) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let rows = buf0.len() / M;
+    if rows < 3 {
+        return;
+    }
+    let last = rows - 2;
+    let source_row = (N / 2).checked_sub(row0).filter(|&k| k < rows);
+
+    for r in 1..=last + 2 * (depth - 1) {
+        for t in 1..=depth {
+            let Some(k) = (r + 2).checked_sub(2 * t) else { break };
+            if k < 1 || k > last {
+                continue;
+            }
+
+            let (src, dst) = if t % 2 == 1 { (&*buf0, &mut *buf1) } else { (&*buf1, &mut *buf0) };
+            for j in 1..M - 1 {
+                let idx = k * M + j;
+                let lap_x = src[idx - M] + src[idx + M] - 2.0 * src[idx];
+                let lap_y = src[idx - 1] + src[idx + 1] - 2.0 * src[idx];
+                dst[idx] = src[idx] + (factor_x * lap_x + factor_y * lap_y);
+            }
+            if source_row == Some(k) {
+                dst[k * M + M / 2] = 100.0;
+            }
+        }
+    }
+}
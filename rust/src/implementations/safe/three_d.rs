@@ -0,0 +1,262 @@
+use std::ops::Range;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use rayon::prelude::*;
+use crate::grid::{band_ranges, DT};
+use crate::grid3::{diffusion_factors3, Grid3, CENTER3, NX, NY, NZ, PLANE};
+use crate::affinity;
+use crate::halo::AtomicRow;
+use crate::sync::{StepCounter, WaitStrategy};
+
+/*
+  3次元熱方程式（7点ステンシル）の安全な実装
+
+  2次元版と同じ4通りの戦略を用意する。
+    - jacobi3_step         : シングルスレッド
+    - rayon3_parallel      : xy平面単位でRayonに分配
+    - barrier3_parallel    : z方向のスラブ分割 + AtomicRow（halo.rs）での境界平面交換 + Barrier
+    - atomic_counter3      : 同じスラブ分割で、隣接スラブとだけアトミックカウンタで同期
+  スラブ分割の版はスレッド数を引数で受け取る（2次元版の上下2分割と違い、任意の分割数を扱う）。
+*/
+
+pub fn jacobi3_step(a: &mut Grid3, b: &mut Grid3, steps: usize) {
+    let (factor_x, factor_y, factor_z) = diffusion_factors3(DT);
+
+    for _ in 0..steps {
+        for k in 1..NZ - 1 {
+            for i in 1..NX - 1 {
+                for j in 1..NY - 1 {
+                    let idx = (k * NX + i) * NY + j;
+                    let v = a.data[idx];
+                    let lap_x = a.data[idx - NY] + a.data[idx + NY] - 2.0 * v;
+                    let lap_y = a.data[idx - 1] + a.data[idx + 1] - 2.0 * v;
+                    let lap_z = a.data[idx - PLANE] + a.data[idx + PLANE] - 2.0 * v;
+                    b.data[idx] = v + (factor_x * lap_x + factor_y * lap_y + factor_z * lap_z);
+                }
+            }
+        }
+
+        b.data[CENTER3] = 100.0;
+
+        std::mem::swap(&mut a.data, &mut b.data);
+    }
+}
+
+pub fn rayon3_parallel(a: &mut Grid3, b: &mut Grid3, steps: usize) {
+    let factors = diffusion_factors3(DT);
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    for _step in 0..steps {
+        let src_ref: &[f64] = src;
+
+        // 内部の平面（1 ～ NZ-2）だけを分配する。外周の平面は両バッファとも初期値のまま
+        dst[PLANE..(NZ - 1) * PLANE]
+            .par_chunks_mut(PLANE)
+            .enumerate()
+            .for_each(|(r, dst_plane)| {
+                let k = r + 1;
+                update_plane(
+                    &src_ref[(k - 1) * PLANE..k * PLANE],
+                    &src_ref[k * PLANE..(k + 1) * PLANE],
+                    &src_ref[(k + 1) * PLANE..(k + 2) * PLANE],
+                    dst_plane,
+                    factors,
+                );
+            });
+
+        dst[CENTER3] = 100.0;
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+pub fn barrier3_parallel(a: &mut Grid3, b: &mut Grid3, steps: usize, num_threads: usize) {
+    let factors = diffusion_factors3(DT);
+    let slabs = band_ranges(NZ, num_threads.clamp(1, NZ));
+    let count = slabs.len();
+
+    let barrier = Arc::new(Barrier::new(count));
+
+    // 各スラブの先頭・末尾の平面（ゴースト平面）の公開用バッファ（ロックの無い AtomicRow。順序はバリアが保証する）
+    let first_planes: Arc<Vec<AtomicRow>> = Arc::new((0..count).map(|_| AtomicRow::new(PLANE)).collect());
+    let last_planes: Arc<Vec<AtomicRow>> = Arc::new((0..count).map(|_| AtomicRow::new(PLANE)).collect());
+
+    let a_slabs = split_slabs(&mut a.data, &slabs);
+    let b_slabs = split_slabs(&mut b.data, &slabs);
+
+    thread::scope(|scope| {
+        for (id, ((range, src), dst)) in slabs.iter().cloned().zip(a_slabs).zip(b_slabs).enumerate() {
+            let barrier = barrier.clone();
+            let first_planes = first_planes.clone();
+            let last_planes = last_planes.clone();
+
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let mut src = src;
+                let mut dst = dst;
+                let planes = range.len();
+                let mut halo_below = vec![0.0; PLANE];
+                let mut halo_above = vec![0.0; PLANE];
+
+                for _step in 0..steps {
+                    // 1. 自分の先頭・末尾の平面を共有バッファに書き出す
+                    first_planes[id].store(&src[..PLANE]);
+                    last_planes[id].store(&src[(planes - 1) * PLANE..]);
+
+                    // バリア: 全スラブが境界を書き込むのを待つ
+                    barrier.wait();
+
+                    // 2. 隣接スラブの境界平面を取得
+                    if id > 0 {
+                        last_planes[id - 1].load_into(&mut halo_below);
+                    }
+                    if id + 1 < count {
+                        first_planes[id + 1].load_into(&mut halo_above);
+                    }
+
+                    // 3. スラブ全体の計算
+                    update_slab(src, dst, &range, &halo_below, &halo_above, factors);
+                    if range.contains(&(NZ / 2)) {
+                        dst[CENTER3 - range.start * PLANE] = 100.0;
+                    }
+
+                    // バリア: 次のステップで共有バッファを上書きする前に、全員の読み出しを待つ
+                    barrier.wait();
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+            });
+        }
+    });
+
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+pub fn atomic_counter3(a: &mut Grid3, b: &mut Grid3, steps: usize, num_threads: usize) {
+    let factors = diffusion_factors3(DT);
+    let slabs = band_ranges(NZ, num_threads.clamp(1, NZ));
+    let count = slabs.len();
+
+    // ready: 境界平面の書き出し完了、done: 隣接スラブの境界平面の読み出しと計算の完了
+    let ready: Arc<Vec<StepCounter>> = Arc::new((0..count).map(|_| StepCounter::new(WaitStrategy::default())).collect());
+    let done: Arc<Vec<StepCounter>> = Arc::new((0..count).map(|_| StepCounter::new(WaitStrategy::default())).collect());
+
+    let first_planes: Arc<Vec<AtomicRow>> = Arc::new((0..count).map(|_| AtomicRow::new(PLANE)).collect());
+    let last_planes: Arc<Vec<AtomicRow>> = Arc::new((0..count).map(|_| AtomicRow::new(PLANE)).collect());
+
+    let a_slabs = split_slabs(&mut a.data, &slabs);
+    let b_slabs = split_slabs(&mut b.data, &slabs);
+
+    thread::scope(|scope| {
+        for (id, ((range, src), dst)) in slabs.iter().cloned().zip(a_slabs).zip(b_slabs).enumerate() {
+            let ready = ready.clone();
+            let done = done.clone();
+            let first_planes = first_planes.clone();
+            let last_planes = last_planes.clone();
+
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let mut src = src;
+                let mut dst = dst;
+                let planes = range.len();
+                let mut halo_below = vec![0.0; PLANE];
+                let mut halo_above = vec![0.0; PLANE];
+                // 同期する相手は上下の隣接スラブだけ
+                let neighbors: Vec<usize> = [id.checked_sub(1), (id + 1 < count).then_some(id + 1)]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                for step in 1..=steps {
+                    first_planes[id].store(&src[..PLANE]);
+                    last_planes[id].store(&src[(planes - 1) * PLANE..]);
+                    ready[id].publish(step);
+
+                    for &n in &neighbors {
+                        ready[n].wait_for(step);
+                    }
+                    if id > 0 {
+                        last_planes[id - 1].load_into(&mut halo_below);
+                    }
+                    if id + 1 < count {
+                        first_planes[id + 1].load_into(&mut halo_above);
+                    }
+
+                    update_slab(src, dst, &range, &halo_below, &halo_above, factors);
+                    if range.contains(&(NZ / 2)) {
+                        dst[CENTER3 - range.start * PLANE] = 100.0;
+                    }
+
+                    done[id].publish(step);
+                    for &n in &neighbors {
+                        done[n].wait_for(step);
+                    }
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+            });
+        }
+    });
+
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// data を z 方向のスラブ（平面の連続区間）ごとの可変スライスに分ける
+fn split_slabs<'a>(data: &'a mut [f64], slabs: &[Range<usize>]) -> Vec<&'a mut [f64]> {
+    let mut rest = data;
+    let mut out = Vec::with_capacity(slabs.len());
+    for range in slabs {
+        let (head, tail) = rest.split_at_mut(range.len() * PLANE);
+        out.push(head);
+        rest = tail;
+    }
+    out
+}
+
+// スラブ内の平面をすべて更新する。スラブの外側の平面は halo_below / halo_above から読む
+fn update_slab(
+    src: &[f64],
+    dst: &mut [f64],
+    range: &Range<usize>,
+    halo_below: &[f64],
+    halo_above: &[f64],
+    factors: (f64, f64, f64),
+) {
+    let planes = range.len();
+    for kl in 0..planes {
+        let k = range.start + kl;
+        // 外周の平面は固定値
+        if k == 0 || k == NZ - 1 {
+            continue;
+        }
+        let below = if kl == 0 { halo_below } else { &src[(kl - 1) * PLANE..kl * PLANE] };
+        let above = if kl + 1 == planes { halo_above } else { &src[(kl + 1) * PLANE..(kl + 2) * PLANE] };
+        update_plane(below, &src[kl * PLANE..(kl + 1) * PLANE], above, &mut dst[kl * PLANE..(kl + 1) * PLANE], factors);
+    }
+}
+
+// xy平面1枚分の更新。below / above は z 方向に隣接する平面
+#[inline(always)]
+fn update_plane(below: &[f64], cur: &[f64], above: &[f64], dst: &mut [f64], factors: (f64, f64, f64)) {
+    let (factor_x, factor_y, factor_z) = factors;
+    for i in 1..NX - 1 {
+        for j in 1..NY - 1 {
+            let p = i * NY + j;
+            let v = cur[p];
+            let lap_x = cur[p - NY] + cur[p + NY] - 2.0 * v;
+            let lap_y = cur[p - 1] + cur[p + 1] - 2.0 * v;
+            let lap_z = below[p] + above[p] - 2.0 * v;
+            dst[p] = v + (factor_x * lap_x + factor_y * lap_y + factor_z * lap_z);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::slice;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use crate::grid::{band_ranges, is_dirichlet, Grid, N, M};
+use crate::implementations::safe::adi::{adi_coefficients, row_sweep, AdiConfig};
+use crate::affinity;
+use crate::shared_grid::SharedPtr;
+
+/*
+  ADI法（Peaceman–Rachford）の帯分割スレッド版
+
+  barrier_unsafe と同じく、生ポインタを各スレッドに渡してバリアで同期する。
+  - 行スイープ: 各スレッドが担当行 [r0, r1) を解き b に書く
+  - 列スイープ: 各スレッドが担当列 [c0, c1) を解き、a の該当列に直接（M要素おきに）書く
+  Rayon版と違い列スイープで一時領域を経由しないため、ストライドアクセスのコストがそのまま現れる。
+*/
+
+pub fn adi_band_unsafe(a: &mut Grid, b: &mut Grid, steps: usize, config: &AdiConfig, num_threads: usize) {
+    let (hx, hy) = adi_coefficients(config.dt);
+    let width = config.column_sweep.width();
+
+    let ptr_a = SharedPtr::new(a.data.as_mut_ptr());
+    let ptr_b = SharedPtr::new(b.data.as_mut_ptr());
+
+    let row_bands = band_ranges(N, num_threads);
+    let col_bands = band_ranges(M, num_threads);
+    let barrier = Arc::new(Barrier::new(row_bands.len()));
+
+    thread::scope(|scope| {
+        for (id, (rows, cols)) in row_bands.into_iter().zip(col_bands).enumerate() {
+            let barrier = barrier.clone();
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let mut cp_row = vec![0.0; M];
+                let mut cp_block = vec![0.0; N * width];
+
+                for _step in 0..steps {
+                    // 前半: 行スイープ a → b
+                    // SAFETY: このフェーズで a は全スレッドから読まれるだけで、
+                    // b の各行は担当スレッドだけが書く。フェーズの境界はバリアで分離される
+                    unsafe {
+                        let src = slice::from_raw_parts(ptr_a.get(), N * M);
+                        for i in rows.clone() {
+                            let dst_row = slice::from_raw_parts_mut(ptr_b.get().add(i * M), M);
+                            row_sweep(src, i, dst_row, &mut cp_row, hx, hy);
+                        }
+                    }
+                    barrier.wait();
+
+                    // 後半: 列スイープ b → a（担当列へ直接書き込む）
+                    let mut j0 = cols.start;
+                    while j0 < cols.end {
+                        let j1 = (j0 + width).min(cols.end);
+                        unsafe {
+                            column_sweep_raw(ptr_b.get(), ptr_a.get(), j0, j1, &mut cp_block, hx, hy);
+                        }
+                        j0 = j1;
+                    }
+                    barrier.wait();
+                }
+            });
+        }
+    });
+}
+
+// 列 [j0, j1) を解いて dst の同じ列に書く
+// SAFETY: 呼び出し側は [j0, j1) 列を他スレッドが同時に書かないこと、
+// このフェーズ中に src が書き換えられないことを保証する
+#[inline(always)]
+unsafe fn column_sweep_raw(
+    src: *const f64,
+    dst: *mut f64,
+    j0: usize,
+    j1: usize,
+    cp: &mut [f64],
+    hx: f64,
+    hy: f64,
+) {
+    let w = j1 - j0;
+    let diag = 1.0 + 2.0 * hx;
+
+    unsafe {
+        // 前進消去
+        for i in 0..N {
+            let src_row = src.add(i * M);
+            let dst_row = dst.add(i * M);
+            for j in j0..j1 {
+                let k = j - j0;
+                let (lower, center, upper, d) = if is_dirichlet(i, j) {
+                    (0.0, 1.0, 0.0, *src_row.add(j))
+                } else {
+                    let v = *src_row.add(j);
+                    (-hx, diag, -hx, v + hy * (*src_row.add(j - 1) - 2.0 * v + *src_row.add(j + 1)))
+                };
+                if i == 0 {
+                    *cp.get_unchecked_mut(k) = upper / center;
+                    *dst_row.add(j) = d / center;
+                } else {
+                    let m = center - lower * *cp.get_unchecked((i - 1) * w + k);
+                    *cp.get_unchecked_mut(i * w + k) = upper / m;
+                    *dst_row.add(j) = (d - lower * *dst_row.add(j).sub(M)) / m;
+                }
+            }
+        }
+
+        // 後退代入
+        for i in (0..N - 1).rev() {
+            let dst_row = dst.add(i * M);
+            for j in j0..j1 {
+                let k = j - j0;
+                *dst_row.add(j) -= *cp.get_unchecked(i * w + k) * *dst_row.add(j + M);
+            }
+        }
+    }
+}
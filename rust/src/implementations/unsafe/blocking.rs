@@ -0,0 +1,73 @@
+use std::ptr;
+use std::thread;
+use crate::affinity;
+use crate::barriers::BandBarrier;
+use crate::grid::{band_ranges, Grid, ALPHA, DT, DX, DY, N, M};
+use crate::shared_grid::SharedPtr;
+
+/*
+  バリアの種類だけを差し替えられる帯分割ソルバー（barriers.rs の BandBarrier）
+
+  barrier_unsafe と同じく2枚のグリッドを直接読み書きし、1ステップごとに全スレッドでバリアを待つ。
+  帯は band_ranges で num_threads 本に分け、同期以外は同じ処理にしてバリアの違いだけを比べる。
+*/
+
+pub fn band_barrier<B: BandBarrier>(grid_a: &mut Grid, grid_b: &mut Grid, steps: usize, num_threads: usize) {
+    let ptr_a = SharedPtr::new(grid_a.data.as_mut_ptr());
+    let ptr_b = SharedPtr::new(grid_b.data.as_mut_ptr());
+    let bands = band_ranges(N, num_threads.clamp(1, N));
+    let barrier = B::new(bands.len());
+    let barrier = &barrier;
+
+    thread::scope(|scope| {
+        for (id, rows) in bands.into_iter().enumerate() {
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                barrier.register(id);
+                let mut src = ptr_a.get();
+                let mut dst = ptr_b.get();
+
+                for _step in 0..steps {
+                    // SAFETY: 各スレッドは dst の自分の帯だけに書き込み、ステップの境界はバリアで分離される
+                    unsafe { band_raw(src, dst, rows.start, rows.end) };
+                    barrier.wait(id);
+                    std::mem::swap(&mut src, &mut dst);
+                }
+            });
+        }
+    });
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(ptr_b.get(), ptr_a.get(), N * M);
+        }
+    }
+}
+
+// 行 [start, end) のうち外周を除いた行を更新する
+// SAFETY: dst のこの範囲の行を他スレッドが同時に読み書きしないこと
+#[inline(always)]
+unsafe fn band_raw(src: *const f64, dst: *mut f64, start: usize, end: usize) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+
+    for i in start.max(1)..end.min(N - 1) {
+        unsafe {
+            let curr_row = src.add(i * M);
+            let up_row = src.add((i - 1) * M);
+            let down_row = src.add((i + 1) * M);
+            let dst_row = dst.add(i * M);
+
+            for j in 1..M - 1 {
+                let v = *curr_row.add(j);
+                let lap_x = *down_row.add(j) + *up_row.add(j) - 2.0 * v;
+                let lap_y = *curr_row.add(j + 1) + *curr_row.add(j - 1) - 2.0 * v;
+                *dst_row.add(j) = v + (factor_x * lap_x + factor_y * lap_y);
+            }
+        }
+    }
+
+    if (start..end).contains(&(N / 2)) {
+        unsafe { *dst.add((N / 2) * M + M / 2) = 100.0 };
+    }
+}
@@ -0,0 +1,155 @@
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use crate::shared_grid::SharedPtr;
+
+/*
+  キャッシュオブリビアスな時空間再帰（Frigo–Strumpen の台形分割）
+
+  タイルの大きさを調整せずにキャッシュ効率を上げるため、時空間の台形を再帰的に分割する。
+  台形は時刻 [t0, t1) と、各次元（行 i・列 j）の区間 [lo + dlo·k, hi + dhi·k)（k = t - t0）で表す。
+  傾き dlo / dhi は -1, 0, +1 のいずれかで、外周に接する辺は 0 になる。
+    - 空間が時間に比べて十分広い: 1つの次元を3つに切る。
+        左右の正立台形（A, C）は互いに独立なので rayon::join で並列に計算し、
+        その後で間の倒立台形（B、幅0から広がる）を計算する
+    - それ以外: 時間を半分に切り、前半・後半の順に計算する
+  依存関係（1ステップで隣接1セル）を守る順序で計算するため、バッファは2枚（時刻の偶奇）で足り、
+  各セルの式と入力は jacobi_step と同じになる。結果はビット単位で一致する。
+*/
+
+// これ以下の体積（セル数 × ステップ数）の台形は分割せずに計算する
+const BASE_CELLS: isize = 1 << 12;
+// これ以下の体積の台形は並列化しない（rayon::join のオーバーヘッドの方が大きい）
+const PARALLEL_CELLS: isize = 1 << 16;
+
+// 台形の1次元分の区間: 時刻 t0 + k で [lo + dlo·k, hi + dhi·k)
+#[derive(Clone, Copy)]
+struct Span {
+    lo: isize,
+    dlo: isize,
+    hi: isize,
+    dhi: isize,
+}
+
+impl Span {
+    fn shift(self, k: isize) -> Span {
+        Span { lo: self.lo + self.dlo * k, hi: self.hi + self.dhi * k, ..self }
+    }
+
+    // 底辺と上辺の平均の幅
+    fn mean_width(&self, dt: isize) -> isize {
+        (2 * (self.hi - self.lo) + (self.dhi - self.dlo) * dt) / 2
+    }
+}
+
+pub fn cache_oblivious_single(a: &mut Grid, b: &mut Grid, steps: usize) {
+    run(a, b, steps, false);
+}
+
+pub fn cache_oblivious_rayon(a: &mut Grid, b: &mut Grid, steps: usize) {
+    run(a, b, steps, true);
+}
+
+fn run(a: &mut Grid, b: &mut Grid, steps: usize, parallel: bool) {
+    // 時刻 t の値は bufs[t % 2] にある
+    let bufs = [SharedPtr::new(a.data.as_mut_ptr()), SharedPtr::new(b.data.as_mut_ptr())];
+    let rows = Span { lo: 1, dlo: 0, hi: (N - 1) as isize, dhi: 0 };
+    let cols = Span { lo: 1, dlo: 0, hi: (M - 1) as isize, dhi: 0 };
+
+    // SAFETY: 内部領域だけを扱い、台形の計算順序は依存関係を守る（上のコメント参照）
+    unsafe {
+        walk(bufs, 0, steps, [rows, cols], parallel);
+    }
+
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+unsafe fn walk(bufs: [SharedPtr<f64>; 2], t0: usize, t1: usize, spans: [Span; 2], parallel: bool) {
+    let dt = (t1 - t0) as isize;
+    if dt == 0 {
+        return;
+    }
+    let volume = spans[0].mean_width(dt) * spans[1].mean_width(dt) * dt;
+    if dt == 1 || volume <= BASE_CELLS {
+        unsafe { base_case(bufs, t0, t1, spans) };
+        return;
+    }
+
+    for d in 0..2 {
+        let s = spans[d];
+        let width = s.hi - s.lo;
+        // 両側の正立台形の上辺が潰れないために必要な幅と、その残り
+        let need = dt * (2 + s.dlo - s.dhi);
+        let slack = width - need;
+        if slack < 2 * dt {
+            continue;
+        }
+
+        let mid = s.lo + dt * (1 + s.dlo) + slack / 2;
+        let mut left = spans;
+        let mut right = spans;
+        let mut center = spans;
+        left[d] = Span { hi: mid, dhi: -1, ..s };
+        right[d] = Span { lo: mid, dlo: 1, ..s };
+        center[d] = Span { lo: mid, dlo: -1, hi: mid, dhi: 1 };
+
+        if parallel && volume > PARALLEL_CELLS {
+            rayon::join(
+                || unsafe { walk(bufs, t0, t1, left, parallel) },
+                || unsafe { walk(bufs, t0, t1, right, parallel) },
+            );
+        } else {
+            unsafe {
+                walk(bufs, t0, t1, left, parallel);
+                walk(bufs, t0, t1, right, parallel);
+            }
+        }
+        unsafe { walk(bufs, t0, t1, center, parallel) };
+        return;
+    }
+
+    // 時間方向に半分に切る
+    let half = dt / 2;
+    let tm = t0 + half as usize;
+    unsafe {
+        walk(bufs, t0, tm, spans, parallel);
+        walk(bufs, tm, t1, [spans[0].shift(half), spans[1].shift(half)], parallel);
+    }
+}
+
+// 台形を1ステップずつそのまま計算する
+unsafe fn base_case(bufs: [SharedPtr<f64>; 2], t0: usize, t1: usize, spans: [Span; 2]) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let center_idx = (N / 2) * M + (M / 2);
+
+    for t in t0..t1 {
+        let k = (t - t0) as isize;
+        let rows = spans[0].shift(k);
+        let cols = spans[1].shift(k);
+        let src = bufs[t % 2].get();
+        let dst = bufs[(t + 1) % 2].get();
+
+        for i in rows.lo..rows.hi {
+            let i = i as usize;
+            unsafe {
+                let curr_row = src.add(i * M);
+                let up_row = src.add((i - 1) * M);
+                let down_row = src.add((i + 1) * M);
+                let dst_row = dst.add(i * M);
+
+                for j in cols.lo..cols.hi {
+                    let j = j as usize;
+                    let v = *curr_row.add(j);
+                    let lap_x = *down_row.add(j) + *up_row.add(j) - 2.0 * v;
+                    let lap_y = *curr_row.add(j + 1) + *curr_row.add(j - 1) - 2.0 * v;
+                    *dst_row.add(j) = v + (factor_x * lap_x + factor_y * lap_y);
+                }
+            }
+        }
+
+        if (rows.lo..rows.hi).contains(&((N / 2) as isize)) && (cols.lo..cols.hi).contains(&((M / 2) as isize)) {
+            unsafe { *dst.add(center_idx) = 100.0 };
+        }
+    }
+}
@@ -1,4 +1,9 @@
 pub mod unsafe_atomic_counter;
 pub mod barrier_unsafe;
 pub mod rayon_unsafe;
-pub mod single_unsafe;
\ No newline at end of file
+pub mod single_unsafe;
+pub mod adi_unsafe;
+pub mod three_d_unsafe;
+pub mod cache_oblivious;
+pub mod pooled;
+pub mod blocking;
\ No newline at end of file
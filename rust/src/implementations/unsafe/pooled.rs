@@ -0,0 +1,60 @@
+use std::ops::Range;
+use crate::grid::{band_ranges, Grid, N};
+use crate::pool::{slots, take, BandPool};
+use crate::shared_grid::{BandSync, SharedGrid};
+use crate::stencil::FivePoint;
+use crate::sync::WaitStrategy;
+
+/*
+  常駐ワーカープールを使う帯分割ソルバー（SharedGrid 版）
+
+  barrier_unsafe / unsafe_atomic_counter と同じ同期方法を、BandPool のスレッドで実行する。
+  スレッドはソルバーの生成時に1回だけ作り、run の間は Condvar で待機しているため、
+  同じソルバーで繰り返し計算すればスレッド生成のコストを含まない定常状態の時間を測れる。
+  帯は band_ranges で threads 本に分け、ワーカー id が帯の番号になる。
+  ワーカーどうしが待ち合わせるので、どれも BandPool::run_synchronized で実行する。
+  AtomicRow で境界行を交換する版は safe::pooled にある。
+*/
+
+pub struct UnsafePooledSolver {
+    pool: BandPool,
+    bands: Vec<Range<usize>>,
+}
+
+impl UnsafePooledSolver {
+    pub fn new(num_threads: usize) -> Self {
+        let bands = band_ranges(N, num_threads.clamp(1, N));
+        UnsafePooledSolver { pool: BandPool::new(bands.len()), bands }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.threads()
+    }
+
+    /// barrier_unsafe と同じく、1ステップごとに全スレッドで Barrier を待つ
+    pub fn barrier(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, steps: usize) {
+        self.run_shared(grid_a, grid_b, steps, BandSync::Barrier);
+    }
+
+    /// unsafe_atomic_counter と同じく、帯ごとのカウンタで隣接する帯の完了だけを待つ
+    pub fn atomic_counter(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, steps: usize) {
+        self.run_shared(grid_a, grid_b, steps, BandSync::Neighbors(WaitStrategy::default()));
+    }
+
+    // SharedGrid の BandToken をワーカーごとに1つずつ渡して steps ステップ進める
+    fn run_shared(&mut self, grid_a: &mut Grid, grid_b: &mut Grid, steps: usize, sync: BandSync) {
+        {
+            let shared = SharedGrid::new(grid_a, grid_b, self.bands.clone(), sync);
+            let tokens = slots(shared.tokens());
+            self.pool.run_synchronized(&|id| {
+                let mut token = take(&tokens, id);
+                for _step in 0..steps {
+                    token.step(|mut view| view.apply(&FivePoint));
+                }
+            });
+        }
+        if !steps.is_multiple_of(2) {
+            grid_a.data.copy_from_slice(&grid_b.data);
+        }
+    }
+}
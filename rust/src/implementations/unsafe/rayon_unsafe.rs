@@ -1,5 +1,10 @@
 use rayon::prelude::*;
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
+use crate::diffusivity::Diffusivity;
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use crate::shared_grid::SharedPtr;
+use crate::simd::{update_row_raw, SimdKernel, SimdLevel};
+use crate::stencil::{FivePoint, Stencil};
+use crate::tiling::TileConfig;
 
 /*
   Rayon Unsafe版
@@ -17,48 +22,51 @@ use crate::grid::{Grid, ALPHA, DT, DX, N, M};
   - 書き込み先を完全に分離することで、ロック不要の並列化を実現
 */
 pub fn rayon_unsafe(a: &mut Grid, b: &mut Grid, steps: usize) {
-    let factor = ALPHA * DT / (DX * DX);
+    rayon_unsafe_stencil(a, b, &FivePoint, steps);
+}
+
+// 可変拡散係数版。面係数も get_unchecked で読み込む
+pub fn rayon_unsafe_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize) {
+    rayon_unsafe_stencil(a, b, coef, steps);
+}
+
+// 本体。セルの更新式は stencil の境界チェックなし版（Stencil::apply_row_unchecked）で計算する
+pub fn rayon_unsafe_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize) {
+    let r = S::RADIUS;
 
     let mut src = &mut a.data[..];
     let mut dst = &mut b.data[..];
 
     for _step in 0..steps {
-        // 境界行（最上行・最下行）は変化しない（Neumann境界条件）
-        dst[0..M].copy_from_slice(&src[0..M]);
-        dst[(N - 1) * M..N * M].copy_from_slice(&src[(N - 1) * M..N * M]);
+        // 外周 r 行は変化しない
+        dst[..r * M].copy_from_slice(&src[..r * M]);
+        dst[(N - r) * M..].copy_from_slice(&src[(N - r) * M..]);
 
-        // 内部領域（1行目 ～ N-2行目）のみをスライスとして切り出す。この部分が並列計算の対象
-        let interior_dst = &mut dst[M..(N - 1) * M];
+        let src_ref: &[f64] = src;
 
         // 書き込み先のグリッドを「行」単位で分割し、複数のCPUコア（スレッド）に分配して同時に計算
         // 各スレッドは異なる行（dst_row）に書き込むため、ロック（Mutexなど）を使わずに安全かつ高速に並列処理が可能
-        interior_dst
+        dst[r * M..(N - r) * M]
             .par_chunks_mut(M) // 行ごとにスライスを分割
             .enumerate() // 各行ごとにインデックスを付与
-            .for_each(|(r, dst_row)| {
-                // rは内部領域での行インデックス (0始まり)
-                // 実際のgrid上の行は r + 1
-                let i = r + 1;
+            .for_each(|(row, dst_row)| {
+                // 実際のgrid上の行は row + r
+                let i = row + r;
 
                 // SAFETY: インデックスは常に有効な範囲内
-                // - i >= 1 かつ i < N-1 が保証されている
-                // - idx の範囲も論理的に保証されている
+                // - r <= i < N-r が保証されているので、row(k) の k も [0, N) にある
+                // - dst_row は M 要素
                 unsafe {
-                    for j in 1..M - 1 {
-                        let idx = i * M + j;
-                        // get_unchecked で境界チェックを回避
-                        let laplacian = *src.get_unchecked(idx - M)
-                            + *src.get_unchecked(idx + M)
-                            + *src.get_unchecked(idx - 1)
-                            + *src.get_unchecked(idx + 1)
-                            - 4.0 * *src.get_unchecked(idx);
-
-                        *dst_row.get_unchecked_mut(j) = *src.get_unchecked(idx) + factor * laplacian;
-                    }
+                    stencil.apply_row_unchecked(
+                        i,
+                        |k| std::slice::from_raw_parts(src_ref.as_ptr().add(k * M), M),
+                        dst_row,
+                    );
 
                     // 境界列のコピー
-                    *dst_row.get_unchecked_mut(0) = *src.get_unchecked(i * M);
-                    *dst_row.get_unchecked_mut(M - 1) = *src.get_unchecked(i * M + M - 1);
+                    for j in (0..r).chain(M - r..M) {
+                        *dst_row.get_unchecked_mut(j) = *src_ref.get_unchecked(i * M + j);
+                    }
                 }
             });
 
@@ -69,7 +77,91 @@ pub fn rayon_unsafe(a: &mut Grid, b: &mut Grid, steps: usize) {
     }
 
     // 結果を a に戻す
-    if steps % 2 != 0 {
+    if !steps.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// 行カーネルを std::arch 版（simd.rs）に置き換えた版
+pub fn rayon_unsafe_simd(a: &mut Grid, b: &mut Grid, steps: usize, level: SimdLevel) {
+    let level = SimdKernel::require(level).level();
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    for _step in 0..steps {
+        dst[0..M].copy_from_slice(&src[0..M]);
+        dst[(N - 1) * M..N * M].copy_from_slice(&src[(N - 1) * M..N * M]);
+
+        let src_ref: &[f64] = src;
+        dst[M..(N - 1) * M]
+            .par_chunks_mut(M)
+            .enumerate()
+            .for_each(|(r, dst_row)| {
+                let i = r + 1;
+
+                // SAFETY: i は [1, N-1) の範囲にあり、参照する3行と dst_row はいずれも M 要素
+                unsafe {
+                    let row = src_ref.as_ptr().add(i * M);
+                    update_row_raw(level, row.sub(M), row, row.add(M), dst_row.as_mut_ptr(), M, factor_x, factor_y);
+
+                    *dst_row.get_unchecked_mut(0) = *row;
+                    *dst_row.get_unchecked_mut(M - 1) = *row.add(M - 1);
+                }
+            });
+
+        dst[(N / 2) * M + M / 2] = 100.0;
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if !steps.is_multiple_of(2) {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+// 空間タイリング版: タイル1枚を1タスクとしてRayonに分配する
+// タイルは行方向に連続しないため、スライス分割ではなく生ポインタで書き込む
+pub fn rayon_unsafe_tiled(a: &mut Grid, b: &mut Grid, steps: usize, tiles: &TileConfig) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let tile_list = tiles.tiles();
+
+    let mut src = SharedPtr::new(a.data.as_mut_ptr());
+    let mut dst = SharedPtr::new(b.data.as_mut_ptr());
+
+    for _step in 0..steps {
+        let (s, d) = (src, dst);
+        tile_list.par_iter().for_each(|(rows, cols)| {
+            let (s, d) = (s.get(), d.get());
+            // SAFETY: タイルは互いに重ならず、各タスクは自分のタイルの内部セルだけを dst に書く。
+            // src はこのステップ中どのタスクからも書き換えられない
+            unsafe {
+                for i in rows.clone() {
+                    let row = s.add(i * M);
+                    let up = row.sub(M);
+                    let down = row.add(M);
+                    let dst_row = d.add(i * M);
+                    for j in cols.clone() {
+                        let v = *row.add(j);
+                        let lap_x = *up.add(j) + *down.add(j) - 2.0 * v;
+                        let lap_y = *row.add(j - 1) + *row.add(j + 1) - 2.0 * v;
+                        *dst_row.add(j) = v + (factor_x * lap_x + factor_y * lap_y);
+                    }
+                }
+            }
+        });
+
+        unsafe {
+            *dst.get().add((N / 2) * M + M / 2) = 100.0;
+        }
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if !steps.is_multiple_of(2) {
         a.data.copy_from_slice(&b.data);
     }
 }
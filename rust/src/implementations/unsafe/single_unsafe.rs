@@ -1,6 +1,10 @@
 use std::mem;
 use std::ptr;
-use crate::grid::{Grid, ALPHA, DT, DX, N, M};
+use crate::diffusivity::Diffusivity;
+use crate::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use crate::simd::{update_row_raw, SimdKernel, SimdLevel};
+use crate::stencil::{FivePoint, Stencil};
+use crate::tiling::TileConfig;
 
 /// 生ポインタを使ったシングルスレッドJacobi法実装
 ///
@@ -8,30 +12,31 @@ use crate::grid::{Grid, ALPHA, DT, DX, N, M};
 /// Safe版のsingle.rsと同じアルゴリズムだが、unsafeブロックを使用して
 /// 配列アクセスの境界チェックを省略している。
 pub fn jacobi_step_unsafe(a: &mut Grid, b: &mut Grid, steps: usize) {
-    let factor = ALPHA * DT / (DX * DX);
+    jacobi_step_unsafe_stencil(a, b, &FivePoint, steps);
+}
+
+/// 可変拡散係数版の生ポインタ実装
+///
+/// 面係数の読み込みも境界チェックなしで行う。
+pub fn jacobi_step_unsafe_var(a: &mut Grid, b: &mut Grid, coef: &Diffusivity, steps: usize) {
+    jacobi_step_unsafe_stencil(a, b, coef, steps);
+}
+
+/// 本体。セルの更新式は stencil の境界チェックなし版（Stencil::apply_row_unchecked）で計算する
+pub fn jacobi_step_unsafe_stencil<S: Stencil>(a: &mut Grid, b: &mut Grid, stencil: &S, steps: usize) {
+    let r = S::RADIUS;
     let center_idx = (N / 2) * M + (M / 2);
 
     let mut src = a.data.as_mut_ptr();
     let mut dst = b.data.as_mut_ptr();
 
     for _ in 0..steps {
+        // SAFETY: src と dst は別の N*M 要素のバッファで、このスレッドだけが触る。i は [r, N-r)
         unsafe {
             // 全グリッドを計算（境界は除く）
-            for i in 1..N-1 {
-                let curr_row = src.add(i * M);
-                let up_row = src.add((i - 1) * M);
-                let down_row = src.add((i + 1) * M);
-                let dst_row = dst.add(i * M);
-
-                for j in 1..M-1 {
-                    let v = *curr_row.add(j);
-                    let laplacian = *curr_row.add(j + 1)
-                                  + *curr_row.add(j - 1)
-                                  + *down_row.add(j)
-                                  + *up_row.add(j)
-                                  - 4.0 * v;
-                    *dst_row.add(j) = v + factor * laplacian;
-                }
+            for i in r..N-r {
+                let dst_row = std::slice::from_raw_parts_mut(dst.add(i * M), M);
+                stencil.apply_row_unchecked(i, |k| std::slice::from_raw_parts(src.add(k * M), M), dst_row);
             }
 
             // 熱源位置を固定温度に設定
@@ -48,3 +53,82 @@ pub fn jacobi_step_unsafe(a: &mut Grid, b: &mut Grid, steps: usize) {
         }
     }
 }
+
+/// 行カーネルを std::arch 版（simd.rs）に置き換えた生ポインタ実装
+///
+/// level がこの CPU で使えない場合は panic する。
+pub fn jacobi_step_unsafe_simd(a: &mut Grid, b: &mut Grid, steps: usize, level: SimdLevel) {
+    let level = SimdKernel::require(level).level();
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let center_idx = (N / 2) * M + (M / 2);
+
+    let mut src = a.data.as_mut_ptr();
+    let mut dst = b.data.as_mut_ptr();
+
+    for _ in 0..steps {
+        // SAFETY: 各行は M 要素で、src と dst は別のバッファ。level の対応は確認済み
+        unsafe {
+            for i in 1..N-1 {
+                update_row_raw(level, src.add((i - 1) * M), src.add(i * M), src.add((i + 1) * M),
+                               dst.add(i * M), M, factor_x, factor_y);
+            }
+
+            *dst.add(center_idx) = 100.0;
+        }
+
+        mem::swap(&mut src, &mut dst);
+    }
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(src, a.data.as_mut_ptr(), N * M);
+        }
+    }
+}
+
+/// 空間タイリング版の生ポインタ実装
+///
+/// タイル内では3行（上・現在・下）の tiles.width 列分だけを繰り返し参照する。
+pub fn jacobi_step_unsafe_tiled(a: &mut Grid, b: &mut Grid, steps: usize, tiles: &TileConfig) {
+    let factor_x = ALPHA * DT / (DX * DX);
+    let factor_y = ALPHA * DT / (DY * DY);
+    let center_idx = (N / 2) * M + (M / 2);
+    let row_blocks = tiles.row_blocks();
+    let col_blocks = tiles.col_blocks();
+
+    let mut src = a.data.as_mut_ptr();
+    let mut dst = b.data.as_mut_ptr();
+
+    for _ in 0..steps {
+        unsafe {
+            for rows in &row_blocks {
+                for cols in &col_blocks {
+                    for i in rows.clone() {
+                        let curr_row = src.add(i * M);
+                        let up_row = src.add((i - 1) * M);
+                        let down_row = src.add((i + 1) * M);
+                        let dst_row = dst.add(i * M);
+
+                        for j in cols.clone() {
+                            let v = *curr_row.add(j);
+                            let lap_x = *down_row.add(j) + *up_row.add(j) - 2.0 * v;
+                            let lap_y = *curr_row.add(j + 1) + *curr_row.add(j - 1) - 2.0 * v;
+                            *dst_row.add(j) = v + (factor_x * lap_x + factor_y * lap_y);
+                        }
+                    }
+                }
+            }
+
+            *dst.add(center_idx) = 100.0;
+        }
+
+        mem::swap(&mut src, &mut dst);
+    }
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(src, a.data.as_mut_ptr(), N * M);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::ptr;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use rayon::prelude::*;
+use crate::grid::{band_ranges, DT};
+use crate::grid3::{diffusion_factors3, Grid3, CENTER3, NX, NY, NZ, PLANE};
+use crate::affinity;
+use crate::shared_grid::SharedPtr;
+use crate::sync::{StepCounter, WaitStrategy};
+
+/*
+  3次元熱方程式（7点ステンシル）の unsafe 実装
+
+  安全版（three_d.rs）と同じ4通りの戦略を、生ポインタと get_unchecked で書く。
+  スラブ分割の版は境界平面をコピーせず、隣接スラブの平面を共有グリッドから直接読む。
+    - barrier3_unsafe        : 1ステップにつき Barrier 1回
+    - atomic_counter3_unsafe : スラブごとのカウンタで、隣接スラブの完了だけを待つ
+*/
+
+pub fn jacobi3_step_unsafe(a: &mut Grid3, b: &mut Grid3, steps: usize) {
+    let factors = diffusion_factors3(DT);
+
+    let mut src = a.data.as_mut_ptr();
+    let mut dst = b.data.as_mut_ptr();
+
+    for _ in 0..steps {
+        unsafe {
+            slab_raw(src, dst, 1, NZ - 1, factors);
+            *dst.add(CENTER3) = 100.0;
+        }
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(src, a.data.as_mut_ptr(), NZ * PLANE);
+        }
+    }
+}
+
+pub fn rayon3_unsafe(a: &mut Grid3, b: &mut Grid3, steps: usize) {
+    let (factor_x, factor_y, factor_z) = diffusion_factors3(DT);
+
+    let mut src = &mut a.data[..];
+    let mut dst = &mut b.data[..];
+
+    for _step in 0..steps {
+        let src_ref: &[f64] = src;
+
+        dst[PLANE..(NZ - 1) * PLANE]
+            .par_chunks_mut(PLANE)
+            .enumerate()
+            .for_each(|(r, dst_plane)| {
+                let k = r + 1;
+
+                // SAFETY: k は [1, NZ-1)、i は [1, NX-1)、j は [1, NY-1) の範囲にある
+                unsafe {
+                    for i in 1..NX - 1 {
+                        for j in 1..NY - 1 {
+                            let p = i * NY + j;
+                            let idx = k * PLANE + p;
+                            let v = *src_ref.get_unchecked(idx);
+                            let lap_x = *src_ref.get_unchecked(idx - NY) + *src_ref.get_unchecked(idx + NY) - 2.0 * v;
+                            let lap_y = *src_ref.get_unchecked(idx - 1) + *src_ref.get_unchecked(idx + 1) - 2.0 * v;
+                            let lap_z = *src_ref.get_unchecked(idx - PLANE) + *src_ref.get_unchecked(idx + PLANE) - 2.0 * v;
+                            *dst_plane.get_unchecked_mut(p) =
+                                v + (factor_x * lap_x + factor_y * lap_y + factor_z * lap_z);
+                        }
+                    }
+                }
+            });
+
+        dst[CENTER3] = 100.0;
+
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if steps % 2 == 1 {
+        a.data.copy_from_slice(&b.data);
+    }
+}
+
+pub fn barrier3_unsafe(a: &mut Grid3, b: &mut Grid3, steps: usize, num_threads: usize) {
+    let factors = diffusion_factors3(DT);
+    let slabs = band_ranges(NZ, num_threads.clamp(1, NZ));
+
+    let ptr_a = SharedPtr::new(a.data.as_mut_ptr());
+    let ptr_b = SharedPtr::new(b.data.as_mut_ptr());
+
+    let barrier = Arc::new(Barrier::new(slabs.len()));
+
+    thread::scope(|scope| {
+        for (id, range) in slabs.into_iter().enumerate() {
+            let barrier = barrier.clone();
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let mut src = ptr_a.get();
+                let mut dst = ptr_b.get();
+                // 外周の平面は計算しない
+                let k0 = range.start.max(1);
+                let k1 = range.end.min(NZ - 1);
+
+                for _step in 0..steps {
+                    // SAFETY: 各スレッドは dst の担当平面 [k0, k1) だけに書き込み、
+                    // src は全員が読むだけ。ステップの境界はバリアで分離される
+                    unsafe {
+                        slab_raw(src, dst, k0, k1, factors);
+                        if (k0..k1).contains(&(NZ / 2)) {
+                            *dst.add(CENTER3) = 100.0;
+                        }
+                    }
+                    barrier.wait();
+                    std::mem::swap(&mut src, &mut dst);
+                }
+            });
+        }
+    });
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(ptr_b.get(), ptr_a.get(), NZ * PLANE);
+        }
+    }
+}
+
+pub fn atomic_counter3_unsafe(a: &mut Grid3, b: &mut Grid3, steps: usize, num_threads: usize) {
+    let factors = diffusion_factors3(DT);
+    let slabs = band_ranges(NZ, num_threads.clamp(1, NZ));
+    let count = slabs.len();
+
+    let ptr_a = SharedPtr::new(a.data.as_mut_ptr());
+    let ptr_b = SharedPtr::new(b.data.as_mut_ptr());
+
+    // 各スラブが完了したステップ数
+    let counters: Arc<Vec<StepCounter>> =
+        Arc::new((0..count).map(|_| StepCounter::new(WaitStrategy::default())).collect());
+
+    thread::scope(|scope| {
+        for (id, range) in slabs.into_iter().enumerate() {
+            let counters = counters.clone();
+            scope.spawn(move || {
+                affinity::pin_worker(id);
+                let mut src = ptr_a.get();
+                let mut dst = ptr_b.get();
+                let k0 = range.start.max(1);
+                let k1 = range.end.min(NZ - 1);
+
+                for step in 0..steps {
+                    // SAFETY: 隣接スラブがステップ step を終えるまで次のステップに進まないため、
+                    // 読む境界平面は常に完成しており、自分が書く平面を他スレッドが読んでいることもない
+                    // （隣接スラブより2ステップ以上先行しない）
+                    unsafe {
+                        slab_raw(src, dst, k0, k1, factors);
+                        if (k0..k1).contains(&(NZ / 2)) {
+                            *dst.add(CENTER3) = 100.0;
+                        }
+                    }
+
+                    counters[id].publish(step + 1);
+                    if id > 0 {
+                        counters[id - 1].wait_for(step + 1);
+                    }
+                    if id + 1 < count {
+                        counters[id + 1].wait_for(step + 1);
+                    }
+
+                    std::mem::swap(&mut src, &mut dst);
+                }
+            });
+        }
+    });
+
+    if steps % 2 == 1 {
+        unsafe {
+            ptr::copy_nonoverlapping(ptr_b.get(), ptr_a.get(), NZ * PLANE);
+        }
+    }
+}
+
+// 平面 [k0, k1) を更新する
+// SAFETY: 1 ≤ k0, k1 ≤ NZ-1 であること。dst の [k0, k1) 平面を他スレッドが同時に読み書きしないこと
+#[inline(always)]
+unsafe fn slab_raw(src: *const f64, dst: *mut f64, k0: usize, k1: usize, factors: (f64, f64, f64)) {
+    let (factor_x, factor_y, factor_z) = factors;
+
+    for k in k0..k1 {
+        for i in 1..NX - 1 {
+            unsafe {
+                let row = k * PLANE + i * NY;
+                let cur = src.add(row);
+                let north = src.add(row - NY);
+                let south = src.add(row + NY);
+                let below = src.add(row - PLANE);
+                let above = src.add(row + PLANE);
+                let dst_row = dst.add(row);
+
+                for j in 1..NY - 1 {
+                    let v = *cur.add(j);
+                    let lap_x = *north.add(j) + *south.add(j) - 2.0 * v;
+                    let lap_y = *cur.add(j - 1) + *cur.add(j + 1) - 2.0 * v;
+                    let lap_z = *below.add(j) + *above.add(j) - 2.0 * v;
+                    *dst_row.add(j) = v + (factor_x * lap_x + factor_y * lap_y + factor_z * lap_z);
+                }
+            }
+        }
+    }
+}
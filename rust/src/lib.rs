@@ -1,2 +1,23 @@
+// ステンシル計算は隣接セルをインデックスで参照するのが自然なため、範囲ループの警告は抑制する
+#![allow(clippy::needless_range_loop)]
+
 pub mod grid;
-pub mod implementations;
\ No newline at end of file
+pub mod memory;
+pub mod grid3;
+pub mod diffusivity;
+pub mod stencil;
+pub mod simd;
+pub mod tiling;
+pub mod decomposition;
+pub mod pool;
+pub mod sync;
+pub mod barriers;
+pub mod spsc;
+pub mod halo;
+pub mod double_buffer;
+pub mod shared_grid;
+pub mod affinity;
+pub mod numa;
+pub mod implementations;
+pub mod scheme;
+pub mod validation;
@@ -1,17 +1,51 @@
 use std::time::Instant;
-use jacobi_rust::grid::{Grid, TIME_STEPS};
-use jacobi_rust::implementations::safe::single::jacobi_step;
-use jacobi_rust::implementations::safe::barrier::barrier_parallel::barrier_parallel;
-use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter;
-use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter;
-use jacobi_rust::implementations::safe::rayon::rayon::rayon_parallel;
-use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::barrier_unsafe;
-use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::rayon_unsafe;
-use jacobi_rust::implementations::unsafe_impl::single_unsafe::jacobi_step_unsafe;
+use jacobi_rust::grid::{Grid, Placement, DT, TIME_STEPS, explicit_dt_limit};
+use jacobi_rust::numa::page_placement;
+use jacobi_rust::memory::{tlb_report, PageMode};
+use jacobi_rust::diffusivity::Diffusivity;
+use jacobi_rust::simd::SimdLevel;
+use jacobi_rust::tiling::TileConfig;
+use jacobi_rust::implementations::safe::single::{jacobi_step, jacobi_step_var, jacobi_step_stencil, jacobi_step_simd, jacobi_step_tiled};
+use jacobi_rust::implementations::safe::barrier::barrier_parallel::{barrier_parallel, barrier_parallel_var, barrier_parallel_bands};
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::{unsafe_atomic_counter, unsafe_atomic_counter_with, unsafe_atomic_counter_var, unsafe_atomic_counter_overlap};
+use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::{atomic_counter, atomic_counter_with, atomic_counter_var, atomic_counter_overlap};
+use jacobi_rust::implementations::safe::rayon::rayon::{rayon_parallel, rayon_parallel_var, rayon_parallel_stencil, rayon_simd, rayon_tiled};
+use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::{barrier_unsafe, barrier_unsafe_var, barrier_unsafe_bands};
+use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::{rayon_unsafe, rayon_unsafe_var, rayon_unsafe_simd, rayon_unsafe_tiled};
+use jacobi_rust::implementations::unsafe_impl::single_unsafe::{jacobi_step_unsafe, jacobi_step_unsafe_var, jacobi_step_unsafe_simd, jacobi_step_unsafe_tiled};
+use jacobi_rust::implementations::safe::crank_nicolson::{crank_nicolson, CrankNicolsonConfig, InnerSolver};
+use jacobi_rust::implementations::safe::adi::{adi_single, adi_rayon, AdiConfig, ColumnSweep};
+use jacobi_rust::implementations::unsafe_impl::adi_unsafe::adi_band_unsafe;
+use jacobi_rust::implementations::safe::runge_kutta::{runge_kutta, Integrator, RungeKuttaConfig};
+use jacobi_rust::implementations::safe::three_d::{jacobi3_step, rayon3_parallel, barrier3_parallel, atomic_counter3};
+use jacobi_rust::implementations::unsafe_impl::three_d_unsafe::{jacobi3_step_unsafe, rayon3_unsafe, barrier3_unsafe, atomic_counter3_unsafe};
+use jacobi_rust::grid3::{Grid3, NX, NY, NZ};
+use jacobi_rust::implementations::safe::temporal::{wavefront_single, wavefront_rayon, TemporalConfig};
+use jacobi_rust::implementations::unsafe_impl::cache_oblivious::{cache_oblivious_single, cache_oblivious_rayon};
+use jacobi_rust::implementations::safe::deep_halo::deep_halo_barrier;
+use jacobi_rust::implementations::safe::block2d::{block_barrier, block_p2p};
+use jacobi_rust::implementations::safe::channel::{channel_mpsc, channel_spsc};
+use jacobi_rust::implementations::safe::scoped_bands::scoped_bands_run;
+use jacobi_rust::double_buffer::BandedDoubleBuffer;
+use jacobi_rust::decomposition::ProcessGrid;
+use jacobi_rust::implementations::safe::pooled::PooledSolver;
+use jacobi_rust::implementations::unsafe_impl::pooled::UnsafePooledSolver;
+use jacobi_rust::pool::BandPool;
+use jacobi_rust::sync::{WaitStats, WaitStrategy};
+use jacobi_rust::halo::HaloSchedule;
+use jacobi_rust::barriers::{BandBarrier, CondvarBarrier, FutexBarrier, ParkBarrier};
+use jacobi_rust::implementations::unsafe_impl::blocking::band_barrier;
+use std::sync::Barrier;
+use jacobi_rust::affinity::{self, PinPolicy};
+use jacobi_rust::stencil::{FivePoint, NinePoint, FourthOrder, Stencil};
+use jacobi_rust::scheme::TimeScheme;
 
 const BENCH_ITERATIONS: usize = 10;
 const BENCH_WARMUP: usize = 5;
 
+// 格子のページの確保方法（コマンドライン引数で1回だけ設定する）
+static PAGE_MODE: std::sync::OnceLock<PageMode> = std::sync::OnceLock::new();
+
 fn main() {
     // コマンドライン引数でスレッド数を指定可能
     let args: Vec<String> = std::env::args().collect();
@@ -29,14 +63,47 @@ fn main() {
         std::process::exit(1);
     }
 
-    // Rayonのスレッド数を設定
+    // 2番目の引数で CPU 固定の方針を指定可能（none / compact / scatter / nosmt / 0,2,4）
+    let pin_policy = args.get(2).map_or(Ok(PinPolicy::None), |s| PinPolicy::parse(s)).unwrap_or_else(|e| {
+        eprintln!("エラー: {e}");
+        std::process::exit(1);
+    });
+    affinity::set_policy(pin_policy).unwrap_or_else(|e| {
+        eprintln!("エラー: {e}");
+        std::process::exit(1);
+    });
+
+    // 3番目の引数で格子のページの確保方法を指定可能（default / 4k / thp / hugetlb）
+    let page_mode = args.get(3).map_or(Ok(PageMode::Default), |s| PageMode::parse(s)).unwrap_or_else(|e| {
+        eprintln!("エラー: {e}");
+        std::process::exit(1);
+    });
+    PAGE_MODE.set(page_mode).unwrap();
+
+    // 4番目の引数で、待ち方の比較に使う設定をカンマ区切りで指定可能（spin / yield:256 / backoff:1024 / park:0 など。既定は PRESETS）
+    let wait_strategies = args.get(4).map_or(Ok(WaitStrategy::PRESETS.to_vec()), |s| s.split(',').map(WaitStrategy::parse).collect()).unwrap_or_else(|e| {
+        eprintln!("エラー: {e}");
+        std::process::exit(1);
+    });
+    // メインスレッドは固定しない（固定すると、後から作るスレッドが同じ CPU のマスクを引き継ぐ）。
+    // 単一スレッドの実装はメインスレッドで動くため、固定の方針によらず OS が CPU を選ぶ
+
+    // Rayonのスレッド数を設定（各ワーカーは起動時に固定する）
     rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads)
+        .start_handler(|index| {
+            affinity::pin_worker(index);
+        })
         .build_global()
         .unwrap();
 
     println!("=== Jacobi法 2D熱方程式ベンチマーク ===");
-    println!("TIME_STEPS: {}, 測定回数: {}, スレッド数: {}\n", TIME_STEPS, BENCH_ITERATIONS, num_threads);
+    println!("TIME_STEPS: {}, 測定回数: {}, スレッド数: {}", TIME_STEPS, BENCH_ITERATIONS, num_threads);
+    println!("SIMD: {}", SimdLevel::detect().label());
+    println!("CPU固定: {}", affinity::describe(num_threads));
+    let probe = Grid::with_options(Placement::MainThread, page_mode);
+    println!("ページ: {} ({})\n", probe.data.mode().label(), tlb_report(&probe.data));
+    drop(probe);
 
     bench("Single Thread", |a, b| jacobi_step(a, b, TIME_STEPS));
     bench("Single Thread Unsafe", |a, b| jacobi_step_unsafe(a, b, TIME_STEPS));
@@ -47,21 +114,194 @@ fn main() {
     bench("Rayon", |a, b| rayon_parallel(a, b, TIME_STEPS));
     bench("Rayon Unsafe", |a, b| rayon_unsafe(a, b, TIME_STEPS));
 
+    // 常駐ワーカープール: スレッド生成を計測区間の外に出した定常状態の時間
+    let mut pooled = UnsafePooledSolver::new(num_threads);
+    bench(&format!("Pooled Barrier Unsafe ({} threads)", pooled.threads()), |a, b| pooled.barrier(a, b, TIME_STEPS));
+    bench(&format!("Pooled Atomic Counter Unsafe ({} threads)", pooled.threads()), |a, b| pooled.atomic_counter(a, b, TIME_STEPS));
+    let mut pooled_safe = PooledSolver::new(num_threads);
+    bench(&format!("Pooled Barrier ({} threads)", pooled_safe.threads()), |a, b| pooled_safe.barrier(a, b, TIME_STEPS));
+    bench(&format!("Pooled Atomic Counter ({} threads)", pooled_safe.threads()), |a, b| pooled_safe.atomic_counter(a, b, TIME_STEPS));
+    drop(pooled_safe);
+    report_spawn_overhead(num_threads);
+
+    // 同期プリミティブの比較: 帯分割の処理は同じで、バリアだけを差し替える
+    bench_band_barrier::<Barrier>(num_threads);
+    bench_band_barrier::<CondvarBarrier>(num_threads);
+    bench_band_barrier::<FutexBarrier>(num_threads);
+    bench_band_barrier::<ParkBarrier>(num_threads);
+
+    // Mutex も unsafe も使わず、帯ごとの double buffer を借りて隣の帯を直接読む（Barrier Unsafe と比べる）
+    // 帯ごとのバッファへのコピーは計測区間の外（RwLock の取得は1ステップあたり帯ごとに最大4回で、計測に含まれる）
+    bench_prepared(
+        &format!("Scoped Bands Safe RwLock ({} threads)", num_threads),
+        |a| BandedDoubleBuffer::from_grid(a, num_threads),
+        |buffers| scoped_bands_run(buffers, TIME_STEPS),
+    );
+
+    // メッセージパッシング: 格子を共有せず、境界行をチャネルで隣の帯へ送る
+    bench(&format!("Channel mpsc ({} threads)", num_threads), |a, b| channel_mpsc(a, b, TIME_STEPS, num_threads));
+    for capacity in [1, 4] {
+        bench(&format!("Channel SPSC ring ({} threads, capacity={})", num_threads, capacity), |a, b| {
+            channel_spsc(a, b, TIME_STEPS, num_threads, capacity)
+        });
+    }
+
+    // NUMA: 各帯を計算するスレッドが最初に書き込んだ格子（帯の分け方は各ソルバーと同じ）
+    report_page_placement(num_threads);
+    let two_bands = Placement::FirstTouch { threads: 2 };
+    let own_bands = Placement::FirstTouch { threads: pooled.threads() };
+    bench_placed("Barrier Unsafe (first-touch)", two_bands, |a, b| barrier_unsafe(a, b, TIME_STEPS));
+    bench_placed("Unsafe Atomic Counter (first-touch)", two_bands, |a, b| unsafe_atomic_counter(a, b, TIME_STEPS));
+    bench_placed(&format!("Pooled Barrier Unsafe ({} threads, first-touch)", pooled.threads()), own_bands, |a, b| pooled.barrier(a, b, TIME_STEPS));
+    drop(pooled);
+
+    // 境界データの待機と内部行の計算の重ね合わせ
+    for schedule in HaloSchedule::ALL {
+        bench(&format!("Safe Atomic Counter {}", schedule.label()), |a, b| { atomic_counter_overlap(a, b, TIME_STEPS, schedule); });
+        bench(&format!("Unsafe Atomic Counter {}", schedule.label()), |a, b| { unsafe_atomic_counter_overlap(a, b, TIME_STEPS, schedule); });
+    }
+    report_wait_times();
+
+    // 待ち方の比較: 実時間と、待っている間も含めたプロセス全体の CPU 時間
+    for &strategy in &wait_strategies {
+        bench_cpu(&format!("Safe Atomic Counter wait={}", strategy.label()), |a, b| atomic_counter_with(a, b, TIME_STEPS, strategy));
+        bench_cpu(&format!("Unsafe Atomic Counter wait={}", strategy.label()), |a, b| unsafe_atomic_counter_with(a, b, TIME_STEPS, strategy));
+    }
+
+    // 手動ベクトル化: この CPU で使える命令セットごとに、自動ベクトル化版（上の4つ）と比べる
+    for level in SimdLevel::available() {
+        bench(&format!("Single Thread SIMD {}", level.label()), |a, b| jacobi_step_simd(a, b, TIME_STEPS, level));
+        bench(&format!("Single Thread Unsafe SIMD {}", level.label()), |a, b| jacobi_step_unsafe_simd(a, b, TIME_STEPS, level));
+        bench(&format!("Rayon SIMD {}", level.label()), |a, b| rayon_simd(a, b, TIME_STEPS, level));
+        bench(&format!("Rayon Unsafe SIMD {}", level.label()), |a, b| rayon_unsafe_simd(a, b, TIME_STEPS, level));
+    }
+
+    // 空間タイリング: タイル幅を変えて行全体の走査（上の Single Thread / Rayon）と比べる
+    // 格子サイズごとの効果は scripts/benchmark_grid_sizes.sh で N, M を変えて測る
+    for tiles in [TileConfig::new(64, 32), TileConfig::new(256, 32), TileConfig::default(), TileConfig::new(1024, 16)] {
+        bench(&format!("Single Thread Tiled {}", tiles.label()), |a, b| jacobi_step_tiled(a, b, TIME_STEPS, &tiles));
+        bench(&format!("Single Thread Unsafe Tiled {}", tiles.label()), |a, b| jacobi_step_unsafe_tiled(a, b, TIME_STEPS, &tiles));
+        bench(&format!("Rayon Tiled {}", tiles.label()), |a, b| rayon_tiled(a, b, TIME_STEPS, &tiles));
+        bench(&format!("Rayon Unsafe Tiled {}", tiles.label()), |a, b| rayon_unsafe_tiled(a, b, TIME_STEPS, &tiles));
+    }
+
+    // 時間方向のブロッキング: depth ステップごとにしか同期・バッファ交換しない
+    for depth in [1, 4, 8, 16] {
+        let config = TemporalConfig { depth, ..Default::default() };
+        bench(&format!("Wavefront Single ({})", config.label()), |a, b| wavefront_single(a, b, TIME_STEPS, &config));
+        bench(&format!("Wavefront Rayon ({})", config.label()), |a, b| wavefront_rayon(a, b, TIME_STEPS, &config));
+    }
+
+    // 2次元ブロック分割: 帯分割（1 x スレッド数）とハローの周長が最小の分け方を比べる
+    for grid in [ProcessGrid::new(num_threads, 1), ProcessGrid::for_threads(num_threads)] {
+        bench(&format!("Block Barrier {}", grid.label()), |a, b| block_barrier(a, b, TIME_STEPS, &grid));
+        bench(&format!("Block P2P {}", grid.label()), |a, b| block_p2p(a, b, TIME_STEPS, &grid));
+    }
+
+    // 深いハロー: 同期回数（1/depth）と重複計算（帯あたり約 2·depth² 行）のトレードオフ
+    for depth in [1, 2, 4, 8, 16] {
+        bench(&format!("Deep Halo Barrier (depth={})", depth), |a, b| deep_halo_barrier(a, b, TIME_STEPS, num_threads, depth));
+    }
+
+    // キャッシュオブリビアスな台形分割: タイル幅や深さを調整しない場合の基準
+    bench("Cache-Oblivious Single", |a, b| cache_oblivious_single(a, b, TIME_STEPS));
+    bench("Cache-Oblivious Rayon", |a, b| cache_oblivious_rayon(a, b, TIME_STEPS));
+
+    // 可変拡散係数: 1セルあたり係数配列2本分の読み込みが増える
+    let coef = Diffusivity::copper_trace();
+    bench("Single Thread (var)", |a, b| jacobi_step_var(a, b, &coef, TIME_STEPS));
+    bench("Single Thread Unsafe (var)", |a, b| jacobi_step_unsafe_var(a, b, &coef, TIME_STEPS));
+    bench("Unsafe Atomic Counter (var)", |a, b| unsafe_atomic_counter_var(a, b, &coef, TIME_STEPS));
+    bench("Safe Atomic Counter (var)", |a, b| atomic_counter_var(a, b, &coef, TIME_STEPS));
+    bench("Barrier (var)", |a, b| barrier_parallel_var(a, b, &coef, TIME_STEPS));
+    bench("Barrier Unsafe (var)", |a, b| barrier_unsafe_var(a, b, &coef, TIME_STEPS));
+    bench("Rayon (var)", |a, b| rayon_parallel_var(a, b, &coef, TIME_STEPS));
+    bench("Rayon Unsafe (var)", |a, b| rayon_unsafe_var(a, b, &coef, TIME_STEPS));
+
+    let cn_jacobi = CrankNicolsonConfig { solver: InnerSolver::Jacobi, ..Default::default() };
+    let cn_cg = CrankNicolsonConfig::default();
+    bench("Crank-Nicolson Jacobi", |a, b| { crank_nicolson(a, b, TIME_STEPS, &cn_jacobi); });
+    bench("Crank-Nicolson CG", |a, b| { crank_nicolson(a, b, TIME_STEPS, &cn_cg); });
+
+    // ADI: 列スイープの走査方法によるキャッシュ挙動の違い
+    let adi_strided = AdiConfig { column_sweep: ColumnSweep::Strided, ..Default::default() };
+    let adi_blocked = AdiConfig::default();
+    bench("ADI Single Strided", |a, b| adi_single(a, b, TIME_STEPS, &adi_strided));
+    bench("ADI Single Blocked", |a, b| adi_single(a, b, TIME_STEPS, &adi_blocked));
+    bench("ADI Rayon Strided", |a, b| adi_rayon(a, b, TIME_STEPS, &adi_strided));
+    bench("ADI Rayon Blocked", |a, b| adi_rayon(a, b, TIME_STEPS, &adi_blocked));
+    bench("ADI Band Unsafe Strided", |a, b| adi_band_unsafe(a, b, TIME_STEPS, &adi_strided, num_threads));
+    bench("ADI Band Unsafe Blocked", |a, b| adi_band_unsafe(a, b, TIME_STEPS, &adi_blocked, num_threads));
+
+    // 高次の陽的積分: ステージ数とバッファ数の増加が並列スケーリングに与える影響
+    for integrator in Integrator::ALL {
+        let config = RungeKuttaConfig { integrator, ..Default::default() };
+        bench(&format!("RK {}", integrator.label()), |a, b| runge_kutta(a, b, TIME_STEPS, &config));
+    }
+
+    // ステンシルの違い: 読み込む隣接セル数と、帯分割で交換するハロー幅
+    bench_stencil(&FivePoint, num_threads);
+    bench_stencil(&NinePoint, num_threads);
+    bench_stencil(&FourthOrder, num_threads);
+
+    // 3次元（7点ステンシル、z方向スラブ分割）
+    println!("\n=== 3D {}x{}x{} ===", NX, NY, NZ);
+    bench3("3D Single Thread", |a, b| jacobi3_step(a, b, TIME_STEPS));
+    bench3("3D Single Thread Unsafe", |a, b| jacobi3_step_unsafe(a, b, TIME_STEPS));
+    bench3("3D Safe Atomic Counter", |a, b| atomic_counter3(a, b, TIME_STEPS, num_threads));
+    bench3("3D Unsafe Atomic Counter", |a, b| atomic_counter3_unsafe(a, b, TIME_STEPS, num_threads));
+    bench3("3D Barrier", |a, b| barrier3_parallel(a, b, TIME_STEPS, num_threads));
+    bench3("3D Barrier Unsafe", |a, b| barrier3_unsafe(a, b, TIME_STEPS, num_threads));
+    bench3("3D Rayon", |a, b| rayon3_parallel(a, b, TIME_STEPS));
+    bench3("3D Rayon Unsafe", |a, b| rayon3_unsafe(a, b, TIME_STEPS));
+
+    compare_schemes();
+
     println!("\n=== ベンチマーク完了 ===");
 }
 
-fn bench<F: Fn(&mut Grid, &mut Grid)>(label: &str, func: F) {
+fn bench<F: FnMut(&mut Grid, &mut Grid)>(label: &str, func: F) {
+    bench_placed(label, Placement::MainThread, func);
+}
+
+// bench と同じ測定だが、setup（格子からの準備）は計測区間の外で行い、run だけを測る
+fn bench_prepared<T>(label: &str, mut setup: impl FnMut(&Grid) -> T, mut run: impl FnMut(&T)) {
     let mut times = Vec::new();
 
     for _ in 0..BENCH_WARMUP {
-        let mut a = Grid::new();
-        let mut b = Grid::new();
+        run(&setup(&Grid::new()));
+    }
+
+    for _ in 0..BENCH_ITERATIONS {
+        let prepared = setup(&Grid::new());
+
+        let start = Instant::now();
+        run(&prepared);
+        times.push(start.elapsed().as_secs_f64());
+    }
+
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    println!("{label}: min={:.6}, avg={:.6}, max={:.6}",
+        times[0],
+        times.iter().sum::<f64>() / times.len() as f64,
+        times[times.len() - 1]
+    );
+}
+
+// bench と同じだが、格子を placement の方法で確保する
+fn bench_placed<F: FnMut(&mut Grid, &mut Grid)>(label: &str, placement: Placement, mut func: F) {
+    let pages = PAGE_MODE.get().copied().unwrap_or_default();
+    let mut times = Vec::new();
+
+    for _ in 0..BENCH_WARMUP {
+        let mut a = Grid::with_options(placement, pages);
+        let mut b = Grid::with_options(placement, pages);
         func(&mut a, &mut b);
     }
 
     for _ in 0..BENCH_ITERATIONS {
-        let mut a = Grid::new();
-        let mut b = Grid::new();
+        let mut a = Grid::with_options(placement, pages);
+        let mut b = Grid::with_options(placement, pages);
 
         let start = Instant::now();
         func(&mut a, &mut b);
@@ -75,4 +315,181 @@ fn bench<F: Fn(&mut Grid, &mut Grid)>(label: &str, func: F) {
         times.iter().sum::<f64>() / times.len() as f64, 
         times[times.len() - 1]
     );
-}
\ No newline at end of file
+}
+
+fn bench_stencil<S: Stencil>(stencil: &S, num_threads: usize) {
+    bench(&format!("Stencil {} Single", S::NAME), |a, b| jacobi_step_stencil(a, b, stencil, TIME_STEPS));
+    bench(&format!("Stencil {} Rayon", S::NAME), |a, b| rayon_parallel_stencil(a, b, stencil, TIME_STEPS));
+    bench(&format!("Stencil {} Barrier (halo={})", S::NAME, S::RADIUS), |a, b| {
+        barrier_parallel_bands(a, b, stencil, TIME_STEPS, num_threads)
+    });
+    bench(&format!("Stencil {} Barrier Unsafe (halo={})", S::NAME, S::RADIUS), |a, b| {
+        barrier_unsafe_bands(a, b, stencil, TIME_STEPS, num_threads)
+    });
+}
+
+// bench と同じ測定で、実時間に加えてプロセスの CPU 時間を表示する
+// cpu/wall が 2 に近いほど、2スレッドとも待っている間も CPU を使い続けている
+fn bench_cpu<F: FnMut(&mut Grid, &mut Grid)>(label: &str, mut func: F) {
+    for _ in 0..BENCH_WARMUP {
+        func(&mut Grid::new(), &mut Grid::new());
+    }
+
+    let (mut wall, mut cpu) = (0.0, 0.0);
+    for _ in 0..BENCH_ITERATIONS {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+
+        let cpu_start = process_cpu_time();
+        let start = Instant::now();
+        func(&mut a, &mut b);
+        wall += start.elapsed().as_secs_f64();
+        cpu += process_cpu_time() - cpu_start;
+    }
+
+    let n = BENCH_ITERATIONS as f64;
+    println!("{label}: wall={:.6}, cpu={:.6}, cpu/wall={:.2}", wall / n, cpu / n, cpu / wall);
+}
+
+// プロセス全体（全スレッド）の CPU 時間 [s]
+fn process_cpu_time() -> f64 {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: ts は有効な timespec
+    unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+    ts.tv_sec as f64 + ts.tv_nsec as f64 * 1e-9
+}
+
+fn bench_band_barrier<B: BandBarrier>(num_threads: usize) {
+    bench(&format!("Band Barrier {} ({} threads)", B::NAME, num_threads), |a, b| band_barrier::<B>(a, b, TIME_STEPS, num_threads));
+}
+
+// bench の3次元版
+fn bench3<F: Fn(&mut Grid3, &mut Grid3)>(label: &str, func: F) {
+    let mut times = Vec::new();
+
+    for _ in 0..BENCH_WARMUP {
+        let mut a = Grid3::new();
+        let mut b = Grid3::new();
+        func(&mut a, &mut b);
+    }
+
+    for _ in 0..BENCH_ITERATIONS {
+        let mut a = Grid3::new();
+        let mut b = Grid3::new();
+
+        let start = Instant::now();
+        func(&mut a, &mut b);
+        times.push(start.elapsed().as_secs_f64());
+    }
+
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    println!("{label}: min={:.6}, avg={:.6}, max={:.6}",
+        times[0],
+        times.iter().sum::<f64>() / times.len() as f64,
+        times[times.len() - 1]
+    );
+}
+
+// スレッド生成のコスト: 生成から終了までと、生成済みのプールで空の仕事を1回実行する時間
+fn report_spawn_overhead(num_threads: usize) {
+    let mut spawn = Vec::new();
+    let mut dispatch = Vec::new();
+    for _ in 0..BENCH_ITERATIONS {
+        let start = Instant::now();
+        let mut pool = BandPool::new(num_threads);
+        pool.run(&|_| {});
+        drop(pool);
+        spawn.push(start.elapsed().as_secs_f64());
+
+        let mut pool = BandPool::new(num_threads);
+        let start = Instant::now();
+        pool.run(&|_| {});
+        dispatch.push(start.elapsed().as_secs_f64());
+    }
+    let avg = |times: &[f64]| times.iter().sum::<f64>() / times.len() as f64;
+    println!("スレッド生成 ({} threads): spawn+join={:.6}, pooled dispatch={:.6}", num_threads, avg(&spawn), avg(&dispatch));
+}
+
+// 格子のページがどの NUMA ノードに置かれたか（/proc/self/numa_maps）
+fn report_page_placement(num_threads: usize) {
+    for placement in [Placement::MainThread, Placement::FirstTouch { threads: num_threads }] {
+        let grid = Grid::with_options(placement, PAGE_MODE.get().copied().unwrap_or_default());
+        match page_placement(&grid.data) {
+            Ok(pages) => println!("ページ配置 ({}): {}", placement.label(), pages.label()),
+            Err(e) => println!("ページ配置 ({}): 取得できません ({e})", placement.label()),
+        }
+    }
+}
+
+// 待ち時間の比較: Overlap で隠れた分だけ Wait-first より待ち時間が短くなる
+fn report_wait_times() {
+    let print = |label: &str, stats: WaitStats| {
+        println!("  {label}: wait={:.6}, blocked={}/{}", stats.wait.as_secs_f64(), stats.blocked, stats.waits);
+    };
+    println!("待ち時間（2スレッドの合計）:");
+    for schedule in HaloSchedule::ALL {
+        let (mut a, mut b) = (Grid::new(), Grid::new());
+        print(&format!("Safe Atomic Counter {}", schedule.label()), atomic_counter_overlap(&mut a, &mut b, TIME_STEPS, schedule));
+        let (mut a, mut b) = (Grid::new(), Grid::new());
+        print(&format!("Unsafe Atomic Counter {}", schedule.label()), unsafe_atomic_counter_overlap(&mut a, &mut b, TIME_STEPS, schedule));
+    }
+}
+
+// 同じ物理時間まで進めたときの、陽解法と陰解法の精度・コスト比較
+fn compare_schemes() {
+    let t_end = TIME_STEPS as f64 * DT;
+    println!("\n=== 時間積分スキーム比較 (t = {:.1}, 陽解法の安定限界 dt = {:.4}) ===", t_end, explicit_dt_limit());
+
+    let reference = TimeScheme::Explicit(RungeKuttaConfig { integrator: Integrator::ForwardEuler, ..Default::default() });
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    let start = Instant::now();
+    reference.run(&mut ref_a, &mut ref_b, reference.steps_for(t_end));
+    println!("{}: time={:.6}", reference.label(), start.elapsed().as_secs_f64());
+
+    for integrator in &Integrator::ALL[1..] {
+        let scheme = TimeScheme::Explicit(RungeKuttaConfig { integrator: *integrator, ..Default::default() });
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        let start = Instant::now();
+        scheme.run(&mut a, &mut b, scheme.steps_for(t_end));
+        let t = start.elapsed().as_secs_f64();
+        let max_diff = a.data.iter()
+            .zip(ref_a.data.iter())
+            .fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+        println!("{}: time={:.6}, stages={}, max_diff_vs_explicit={:.3e}",
+            scheme.label(), t, integrator.stages(), max_diff);
+    }
+
+    for factor in [1.0, 10.0, 50.0] {
+        for solver in [InnerSolver::Jacobi, InnerSolver::ConjugateGradient] {
+            let scheme = TimeScheme::CrankNicolson(CrankNicolsonConfig {
+                dt: DT * factor,
+                solver,
+                ..Default::default()
+            });
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            let start = Instant::now();
+            let stats = scheme.run(&mut a, &mut b, scheme.steps_for(t_end));
+            let t = start.elapsed().as_secs_f64();
+
+            let max_diff = a.data.iter()
+                .zip(ref_a.data.iter())
+                .fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+            println!("{}: time={:.6}, inner_iterations={}, max_diff_vs_explicit={:.3e}",
+                scheme.label(), t, stats.inner_iterations, max_diff);
+        }
+
+        let scheme = TimeScheme::Adi(AdiConfig { dt: DT * factor, ..Default::default() });
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        let start = Instant::now();
+        scheme.run(&mut a, &mut b, scheme.steps_for(t_end));
+        let t = start.elapsed().as_secs_f64();
+        let max_diff = a.data.iter()
+            .zip(ref_a.data.iter())
+            .fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+        println!("{}: time={:.6}, max_diff_vs_explicit={:.3e}", scheme.label(), t, max_diff);
+    }
+}
@@ -0,0 +1,273 @@
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/*
+  格子のメモリ確保方法（ページサイズとアラインメント）
+
+  格子が数百 MB になると、4 KiB ページでは TLB に載りきらず TLB ミスが増える。
+    - Default   : 64 バイト（キャッシュライン）アラインメント。従来の AVec<f64, Align64> と同じ
+    - Aligned4K : 4 KiB（ページ）アラインメント
+    - HugePage  : 2 MiB アラインメントで確保し、madvise(MADV_HUGEPAGE) で透過的ヒュージページ（THP）を要求する
+    - HugeTlb   : mmap(MAP_HUGETLB) で hugetlbfs の 2 MiB ページを確保する
+                  予約済みのページ（/proc/sys/vm/nr_hugepages）が無いと失敗するため、その場合は HugePage で確保する
+  HugePage / HugeTlb は確保量を 2 MiB の倍数に切り上げる。実際に使われた方法は GridBuffer::mode で分かる。
+  確保したページにはまだ何も書き込まないため、どのスレッドが最初に書き込むか（grid.rs の Placement）は呼び出し側で決める。
+*/
+
+pub const HUGE_PAGE_SIZE: usize = 2 << 20;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PageMode {
+    #[default]
+    Default,
+    Aligned4K,
+    HugePage,
+    HugeTlb,
+}
+
+impl PageMode {
+    pub const ALL: [PageMode; 4] = [PageMode::Default, PageMode::Aligned4K, PageMode::HugePage, PageMode::HugeTlb];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PageMode::Default => "default",
+            PageMode::Aligned4K => "4k",
+            PageMode::HugePage => "thp",
+            PageMode::HugeTlb => "hugetlb",
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<PageMode, String> {
+        let text = text.trim().to_ascii_lowercase();
+        PageMode::ALL
+            .into_iter()
+            .find(|mode| mode.label() == text)
+            .ok_or_else(|| format!("unknown page mode: {text}"))
+    }
+
+    pub fn alignment(&self) -> usize {
+        match self {
+            PageMode::Default => 64,
+            PageMode::Aligned4K => 4096,
+            PageMode::HugePage | PageMode::HugeTlb => HUGE_PAGE_SIZE,
+        }
+    }
+}
+
+enum Backing {
+    Heap(Layout),
+    Mmap(usize), // マップしたバイト数
+}
+
+/// PageMode の方法で確保した f64 の配列（スライスとして使う）
+pub struct GridBuffer {
+    ptr: NonNull<f64>,
+    len: usize,
+    mode: PageMode,
+    backing: Backing,
+}
+
+// GridBuffer は確保した領域を単独で所有する（Vec と同じ）
+unsafe impl Send for GridBuffer {}
+unsafe impl Sync for GridBuffer {}
+
+impl GridBuffer {
+    /// len 要素を確保する。中身は初期化されていない
+    ///
+    /// # Safety
+    /// 読み出す前に全要素を書き込むこと
+    pub unsafe fn uninit(len: usize, mode: PageMode) -> Self {
+        if mode == PageMode::HugeTlb {
+            if let Some(buffer) = map_hugetlb(len) {
+                return buffer;
+            }
+            return unsafe { GridBuffer::uninit(len, PageMode::HugePage) };
+        }
+
+        let align = mode.alignment();
+        let bytes = (len * size_of::<f64>()).max(1).next_multiple_of(if mode == PageMode::HugePage { align } else { 1 });
+        let layout = Layout::from_size_align(bytes, align).expect("invalid grid layout");
+        // SAFETY: layout のサイズは 0 でない
+        let ptr = NonNull::new(unsafe { alloc::alloc(layout) } as *mut f64).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        if mode == PageMode::HugePage {
+            advise_hugepage(ptr.as_ptr() as *mut u8, bytes);
+        }
+        GridBuffer { ptr, len, mode, backing: Backing::Heap(layout) }
+    }
+
+    /// 0 で埋めた len 要素（現在のスレッドが全ページに最初に書き込む）
+    pub fn zeroed(len: usize, mode: PageMode) -> Self {
+        // SAFETY: 直後に全要素を書き込む（0.0 のビット列は全ビット 0）
+        let mut buffer = unsafe { GridBuffer::uninit(len, mode) };
+        unsafe { std::ptr::write_bytes(buffer.as_mut_ptr(), 0, len) };
+        buffer
+    }
+
+    pub fn from_slice(values: &[f64], mode: PageMode) -> Self {
+        // SAFETY: 直後に全要素をコピーする
+        let mut buffer = unsafe { GridBuffer::uninit(values.len(), mode) };
+        unsafe { std::ptr::copy_nonoverlapping(values.as_ptr(), buffer.as_mut_ptr(), values.len()) };
+        buffer
+    }
+
+    /// 先頭要素へのポインタ
+    ///
+    /// DerefMut と違って &mut [f64] を作らないので、uninit の直後（初期化前）にも使える。
+    pub fn as_mut_ptr(&mut self) -> *mut f64 {
+        self.ptr.as_ptr()
+    }
+
+    /// 実際に使われた確保方法（HugeTlb が使えなかった場合は HugePage）
+    pub fn mode(&self) -> PageMode {
+        self.mode
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f64] {
+        self
+    }
+}
+
+impl Deref for GridBuffer {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        // SAFETY: ptr は len 要素分の初期化済み領域を指す
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for GridBuffer {
+    fn deref_mut(&mut self) -> &mut [f64] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Clone for GridBuffer {
+    fn clone(&self) -> Self {
+        GridBuffer::from_slice(self, self.mode)
+    }
+}
+
+impl std::fmt::Debug for GridBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Drop for GridBuffer {
+    fn drop(&mut self) {
+        match self.backing {
+            // SAFETY: uninit で同じ layout を使って確保した
+            Backing::Heap(layout) => unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout) },
+            Backing::Mmap(bytes) => unmap(self.ptr.as_ptr() as *mut u8, bytes),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn advise_hugepage(ptr: *mut u8, bytes: usize) {
+    // 失敗しても通常のページで動くので結果は見ない（THP が never の環境など）
+    // SAFETY: ptr から bytes バイトは確保済みで、2 MiB 境界に揃っている
+    unsafe { libc::madvise(ptr as *mut libc::c_void, bytes, libc::MADV_HUGEPAGE) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise_hugepage(_ptr: *mut u8, _bytes: usize) {}
+
+#[cfg(target_os = "linux")]
+fn map_hugetlb(len: usize) -> Option<GridBuffer> {
+    let bytes = (len * size_of::<f64>()).max(1).next_multiple_of(HUGE_PAGE_SIZE);
+    // SAFETY: 新しい匿名マッピングを作るだけで、既存のメモリには触れない
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            bytes,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
+            -1,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return None;
+    }
+    Some(GridBuffer {
+        ptr: NonNull::new(ptr as *mut f64)?,
+        len,
+        mode: PageMode::HugeTlb,
+        backing: Backing::Mmap(bytes),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn map_hugetlb(_len: usize) -> Option<GridBuffer> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn unmap(ptr: *mut u8, bytes: usize) {
+    // SAFETY: map_hugetlb で作ったマッピング全体を解放する
+    unsafe { libc::munmap(ptr as *mut libc::c_void, bytes) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unmap(_ptr: *mut u8, _bytes: usize) {}
+
+/// TLB に関係する情報（ベンチマーク出力用）
+///
+/// システムの THP 設定と hugetlbfs の空きページ数、data を含むマッピングのページサイズと THP の量。
+pub fn tlb_report(data: &[f64]) -> String {
+    let read = |path: &str| std::fs::read_to_string(path).ok();
+
+    // "always [madvise] never" の括弧の中が現在の設定
+    let thp = read("/sys/kernel/mm/transparent_hugepage/enabled")
+        .and_then(|s| Some(s.split_once('[')?.1.split_once(']')?.0.to_string()))
+        .unwrap_or_else(|| "-".to_string());
+    let meminfo = read("/proc/meminfo").unwrap_or_default();
+    let meminfo_field = |key: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .map_or("-".to_string(), |v| v.trim().to_string())
+    };
+
+    let (page_size, anon_huge) = mapping_pages(data, &read("/proc/self/smaps").unwrap_or_default());
+    format!(
+        "THP={thp}, Hugepagesize={}, HugePages_Free={}, grid: KernelPageSize={page_size}, AnonHugePages={anon_huge}",
+        meminfo_field("Hugepagesize"),
+        meminfo_field("HugePages_Free"),
+    )
+}
+
+// smaps から data を含むマッピングの KernelPageSize と AnonHugePages を探す
+fn mapping_pages(data: &[f64], smaps: &str) -> (String, String) {
+    let addr = data.as_ptr() as usize;
+    let mut inside = false;
+    let (mut page_size, mut anon_huge) = ("-".to_string(), "-".to_string());
+
+    for line in smaps.lines() {
+        // マッピングの見出し行は "start-end perms ..."
+        let range = line
+            .split_once(' ')
+            .and_then(|(range, _)| range.split_once('-'))
+            .and_then(|(s, e)| Some((usize::from_str_radix(s, 16).ok()?, usize::from_str_radix(e, 16).ok()?)));
+        if let Some((start, end)) = range {
+            if inside {
+                break;
+            }
+            inside = (start..end).contains(&addr);
+        } else if inside {
+            if let Some(v) = line.strip_prefix("KernelPageSize:") {
+                page_size = v.trim().to_string();
+            } else if let Some(v) = line.strip_prefix("AnonHugePages:") {
+                anon_huge = v.trim().to_string();
+            }
+        }
+    }
+    (page_size, anon_huge)
+}
@@ -0,0 +1,75 @@
+/*
+  NUMA ノードごとのページ配置の確認（/proc/self/numa_maps）
+
+  numa_maps はメモリマッピング（VMA）ごとに「N<ノード>=<ページ数>」を並べたもので、
+  /proc/self/maps でバッファの先頭アドレスを含むマッピングを探し、その行を読む。
+  カーネルは隣り合う同じ属性の匿名マッピングを1つにまとめるため、
+  同時に確保した別のバッファ（a と b など）のページも合わせて数えられることがある。
+*/
+
+/// 1つのマッピングのノード別ページ数
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PagePlacement {
+    pub nodes: Vec<(usize, usize)>, // (ノード番号, ページ数)
+    pub page_kb: usize,
+}
+
+impl PagePlacement {
+    pub fn total_pages(&self) -> usize {
+        self.nodes.iter().map(|&(_, pages)| pages).sum()
+    }
+
+    /// "N0=64 N1=64 (4kB pages)"
+    pub fn label(&self) -> String {
+        let nodes: Vec<String> = self.nodes.iter().map(|(node, pages)| format!("N{node}={pages}")).collect();
+        format!("{} ({}kB pages)", nodes.join(" "), self.page_kb)
+    }
+}
+
+/// data を含むマッピングのページ配置
+#[cfg(target_os = "linux")]
+pub fn page_placement<T>(data: &[T]) -> std::io::Result<PagePlacement> {
+    let addr = data.as_ptr() as usize;
+    let not_found = || std::io::Error::new(std::io::ErrorKind::NotFound, format!("no mapping for {addr:#x}"));
+
+    // maps の各行は "start-end perms ..."（16進）
+    let maps = std::fs::read_to_string("/proc/self/maps")?;
+    let start = maps
+        .lines()
+        .filter_map(|line| {
+            let (range, _) = line.split_once(' ')?;
+            let (start, end) = range.split_once('-')?;
+            Some((usize::from_str_radix(start, 16).ok()?, usize::from_str_radix(end, 16).ok()?))
+        })
+        .find(|&(start, end)| (start..end).contains(&addr))
+        .map(|(start, _)| start)
+        .ok_or_else(not_found)?;
+
+    // numa_maps の各行は "start policy key=value ..."
+    let numa_maps = std::fs::read_to_string("/proc/self/numa_maps")?;
+    let line = numa_maps
+        .lines()
+        .find(|line| line.split(' ').next().and_then(|s| usize::from_str_radix(s, 16).ok()) == Some(start))
+        .ok_or_else(not_found)?;
+
+    Ok(parse_numa_maps_line(line))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn page_placement<T>(_data: &[T]) -> std::io::Result<PagePlacement> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+/// numa_maps の1行から N<ノード>=<ページ数> と kernelpagesize_kB を取り出す
+pub fn parse_numa_maps_line(line: &str) -> PagePlacement {
+    let mut placement = PagePlacement { nodes: Vec::new(), page_kb: 4 };
+    for (key, value) in line.split_whitespace().filter_map(|field| field.split_once('=')) {
+        let Ok(value) = value.parse::<usize>() else { continue };
+        if key == "kernelpagesize_kB" {
+            placement.page_kb = value;
+        } else if let Some(node) = key.strip_prefix('N').and_then(|n| n.parse::<usize>().ok()) {
+            placement.nodes.push((node, value));
+        }
+    }
+    placement
+}
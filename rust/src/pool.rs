@@ -0,0 +1,164 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use crate::affinity;
+
+/*
+  使い回せるワーカースレッドのプール
+
+  barrier_parallel などは呼び出しごとに thread::scope でスレッドを作るため、
+  ベンチマークの計測区間にスレッド生成のコストが含まれる。
+  BandPool は生成時に threads 本のスレッドを作り、run のたびに同じスレッドで仕事（ジョブ）を実行する。
+  仕事が無い間、ワーカーは Condvar で待機する（スピンしない）。
+
+  run(job) は各ワーカーで job(id)（id = 0..threads）を1回ずつ呼び、全員が終わるまで戻らない。
+  そのため job は呼び出し元のスタック上の値を借用してよい（thread::scope と同じ使い方）。
+*/
+
+// ワーカーに渡すジョブ。run が全員の完了を待つ間だけ有効
+#[derive(Clone, Copy)]
+struct Job(*const (dyn Fn(usize) + Sync));
+unsafe impl Send for Job {}
+
+struct State {
+    generation: u64,     // run のたびに1増える。ワーカーはこれが変わったら起きる
+    job: Option<Job>,
+    remaining: usize,    // まだ job を終えていないワーカーの数
+    panicked: bool,
+    shutdown: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    start: Condvar,
+    finished: Condvar,
+}
+
+pub struct BandPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl BandPool {
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State { generation: 0, job: None, remaining: 0, panicked: false, shutdown: false }),
+            start: Condvar::new(),
+            finished: Condvar::new(),
+        });
+
+        let workers = (0..threads)
+            .map(|id| {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name(format!("band-worker-{id}"))
+                    .spawn(move || worker_loop(id, &shared))
+                    .expect("failed to spawn band worker")
+            })
+            .collect();
+
+        BandPool { shared, workers }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// 全ワーカーで job(id) を実行し、全員が終わるまで待つ
+    ///
+    /// ワーカーの中で panic した場合は、全員が終わった後に呼び出し元で panic する。
+    /// ただし job の中でワーカーどうしが待ち合わせる（バリアやカウンタ）場合、panic したワーカーを
+    /// 他のワーカーが待ち続けるため、全員が終わらず run は戻らない。そのような job には run_synchronized を使う。
+    pub fn run(&mut self, job: &(dyn Fn(usize) + Sync)) {
+        // SAFETY: 借用の寿命を消してワーカーに渡すが、この関数は全ワーカーが job を使い終えるまで戻らず、
+        // 戻る前に job を取り除くため、寿命を超えて参照されることはない
+        let job: &'static (dyn Fn(usize) + Sync) = unsafe { std::mem::transmute(job) };
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.job = Some(Job(job));
+        state.remaining = self.workers.len();
+        state.generation += 1;
+        self.shared.start.notify_all();
+
+        while state.remaining > 0 {
+            state = self.shared.finished.wait(state).unwrap();
+        }
+        state.job = None;
+
+        if std::mem::take(&mut state.panicked) {
+            drop(state);
+            panic!("a band worker panicked");
+        }
+    }
+
+    /// ワーカーどうしが待ち合わせる job 用の run
+    ///
+    /// 1つのワーカーが panic すると他のワーカーが待ち続けて戻らなくなるため、
+    /// panic をメッセージとして表示した後、プロセスを中断する（呼び出し元には戻らない）。
+    pub fn run_synchronized(&mut self, job: &(dyn Fn(usize) + Sync)) {
+        self.run(&|id| {
+            let guard = AbortOnUnwind;
+            job(id);
+            std::mem::forget(guard);
+        });
+    }
+}
+
+// job が正常に戻れば forget され、panic で巻き戻るときだけ drop される
+struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        eprintln!("a band worker panicked while the other workers wait on it; aborting");
+        std::process::abort();
+    }
+}
+
+// run の job は Fn なので、ワーカーごとの値は Mutex<Option<_>> に入れて、各ワーカーが自分の分を1回だけ取り出す
+pub(crate) fn slots<T>(values: impl IntoIterator<Item = T>) -> Vec<Mutex<Option<T>>> {
+    values.into_iter().map(|value| Mutex::new(Some(value))).collect()
+}
+
+pub(crate) fn take<T>(slots: &[Mutex<Option<T>>], id: usize) -> T {
+    slots[id].lock().unwrap().take().expect("each worker takes its band once")
+}
+
+impl Drop for BandPool {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.start.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn worker_loop(id: usize, shared: &Shared) {
+    // 常駐するので、固定は起動時の1回だけでよい
+    affinity::pin_worker(id);
+    let mut seen = 0;
+    loop {
+        let job = {
+            let mut state = shared.state.lock().unwrap();
+            while state.generation == seen && !state.shutdown {
+                state = shared.start.wait(state).unwrap();
+            }
+            if state.shutdown {
+                return;
+            }
+            seen = state.generation;
+            state.job.unwrap()
+        };
+
+        // SAFETY: run が完了を待っている間だけ job は有効
+        let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { (*job.0)(id) }));
+
+        let mut state = shared.state.lock().unwrap();
+        state.panicked |= result.is_err();
+        state.remaining -= 1;
+        if state.remaining == 0 {
+            shared.finished.notify_all();
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Barrier;
+use crate::grid::{Grid, M, N};
+use crate::stencil::Stencil;
+use crate::sync::{StepCounter, StepSignal, WaitStats, WaitStrategy};
+
+/*
+  帯分割で2枚の格子を共有するための、unsafe を閉じ込めた型
+
+  barrier_unsafe の GridPtr や unsafe_atomic_counter の GridHandle は、生ポインタを各スレッドに配り、
+  「自分の帯だけに書く」「相手が書き終えたバッファだけを読む」ことを実装ごとに手で保証していた。
+  SharedGrid はこの保証を1か所にまとめ、外からは safe な API だけを使う。
+    - SharedGrid::new で2枚の格子を借用し、0..N を隙間なく覆う帯の分け方を受け取る
+    - tokens で帯ごとに1つだけ BandToken を発行する（2回目の呼び出しは panic）
+    - BandToken::step で1ステップ分の BandView を借りて計算し、戻る前に同期する
+  BandView が貸すのは次の2つだけで、どちらも step の中でしか使えない（クロージャの外へ持ち出せない）。
+    - 読み: 読む側のバッファ（step の偶奇で決まる）の、自分の帯と上下 halo 行ずつ（ハロー。既定は1行で、with_halo で広げる）
+    - 書き: 書く側のバッファの、自分の帯の行
+
+  同期（BandSync）と、借用が重ならない理由:
+    - Barrier   : 全帯が同じステップにいる。ステップ s で書かれるのは書く側のバッファだけで、読む側は誰も書かない
+    - Neighbors : 帯 k はステップ s を計算する前に、上下の帯がステップ s-1 を終えるまで待つ。
+                  帯 k がステップ s で書くバッファの自分の帯を読むのは、上下の帯のステップ s-1 だけ。
+                  帯 k がステップ s で読む上下のハローは、上下の帯がステップ s-1 で書き終えている。
+                  上下の帯のステップ s+1 は、帯 k のステップ s の完了を待ってから始まる。
+                  離れた帯は先に進んでいてもよいが、ハローより外は読まないので、同じ行を同時に読み書きすることは無い
+                  （各帯が halo 行以上あるので、ハローは上下の帯に収まる）
+  step_overlapped（Neighbors のみ）は、上下の帯のハローに入らない内部行を待つ前に計算する。
+  内部行は上下の帯のどのステップからも読まれず、計算に使うのも自分の帯の行だけなので、待たずに書いてよい。
+  ステップ数が奇数なら最新の結果は b にある（呼び出し側で a にコピーする）。
+*/
+
+/// 1ステップごとの同期方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BandSync {
+    Barrier,
+    Neighbors(WaitStrategy),
+}
+
+/// 2枚の格子と帯の分け方。C は Neighbors で使うカウンタの型（既定は StepCounter）
+pub struct SharedGrid<'g, C = StepCounter> {
+    buffers: [NonNull<f64>; 2],
+    bands: Vec<Range<usize>>,
+    halo: usize,
+    timed: bool,
+    barrier: Option<Barrier>, // Barrier のときだけ。None なら Neighbors
+    counters: Vec<C>,
+    issued: AtomicBool,
+    _grids: PhantomData<&'g mut [f64]>,
+}
+
+// SAFETY: 2枚の格子は 'g の間 SharedGrid が排他的に借用している。
+// スレッドから触れるのは BandToken::step を通じてだけで、そこで貸す範囲は上の説明のとおり重ならない
+unsafe impl<C: Sync> Sync for SharedGrid<'_, C> {}
+
+impl<'g> SharedGrid<'g> {
+    /// bands は 0..N を順に隙間なく覆うこと（そうでなければ panic）
+    pub fn new(a: &'g mut Grid, b: &'g mut Grid, bands: Vec<Range<usize>>, sync: BandSync) -> Self {
+        match sync {
+            BandSync::Barrier => SharedGrid::build(a, b, bands, true, || StepCounter::new(WaitStrategy::default())),
+            BandSync::Neighbors(strategy) => SharedGrid::build(a, b, bands, false, || StepCounter::new(strategy)),
+        }
+    }
+}
+
+impl<'g, C: StepSignal> SharedGrid<'g, C> {
+    /// Neighbors で同期し、帯ごとのカウンタを counter() で作る（StepCounter 以外のカウンタを使う場合）
+    pub fn with_counters(a: &'g mut Grid, b: &'g mut Grid, bands: Vec<Range<usize>>, counter: impl Fn() -> C) -> Self {
+        SharedGrid::build(a, b, bands, false, counter)
+    }
+
+    fn build(a: &'g mut Grid, b: &'g mut Grid, bands: Vec<Range<usize>>, barrier: bool, counter: impl Fn() -> C) -> Self {
+        assert!(a.data.len() == N * M && b.data.len() == N * M, "grid size mismatch");
+        assert!(!bands.is_empty(), "no bands");
+        let mut next = 0;
+        for rows in &bands {
+            assert!(rows.start == next && rows.end > rows.start, "bands must tile 0..N in order");
+            next = rows.end;
+        }
+        assert_eq!(next, N, "bands must tile 0..N in order");
+
+        SharedGrid {
+            buffers: [NonNull::from(a.data.as_mut_slice()).cast(), NonNull::from(b.data.as_mut_slice()).cast()],
+            barrier: barrier.then(|| Barrier::new(bands.len())),
+            counters: bands.iter().map(|_| counter()).collect(),
+            bands,
+            halo: 1,
+            timed: false,
+            issued: AtomicBool::new(false),
+            _grids: PhantomData,
+        }
+    }
+
+    /// ハロー（上下に読める行数）を width 行にする。各帯は width 行以上であること（そうでなければ panic）
+    pub fn with_halo(mut self, width: usize) -> Self {
+        assert!(self.bands.iter().all(|rows| rows.len() >= width), "each band needs at least `width` rows");
+        self.halo = width;
+        self
+    }
+
+    /// Neighbors の待機を BandToken::wait_stats で計測する
+    pub fn timed(mut self) -> Self {
+        self.timed = true;
+        self
+    }
+
+    /// 帯ごとの BandToken（id は帯の番号）。1つの SharedGrid につき1回だけ呼べる
+    pub fn tokens(&self) -> Vec<BandToken<'_, 'g, C>> {
+        assert!(!self.issued.swap(true, Ordering::AcqRel), "band tokens were already issued");
+        (0..self.bands.len()).map(|id| BandToken { grid: self, id, step: 0, stats: self.timed.then(WaitStats::default) }).collect()
+    }
+}
+
+/// 1つの帯を計算する権利。スレッドに move して使う
+pub struct BandToken<'s, 'g, C = StepCounter> {
+    grid: &'s SharedGrid<'g, C>,
+    id: usize,
+    step: usize,
+    stats: Option<WaitStats>,
+}
+
+impl<C: StepSignal> BandToken<'_, '_, C> {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn rows(&self) -> Range<usize> {
+        self.grid.bands[self.id].clone()
+    }
+
+    /// これまでに終えたステップ数
+    pub fn steps_done(&self) -> usize {
+        self.step
+    }
+
+    /// SharedGrid::timed のとき、これまでの待機の計測結果（それ以外は空）
+    pub fn wait_stats(&self) -> WaitStats {
+        self.stats.unwrap_or_default()
+    }
+
+    /// 1ステップ分の BandView で f を呼び、他の帯と同期してから戻る
+    pub fn step<R>(&mut self, f: impl FnOnce(BandView<'_>) -> R) -> R {
+        let rows = self.rows();
+        self.wait_neighbors();
+        // SAFETY: 待った後なので、rows は帯の担当行全体
+        let result = f(unsafe { self.view(rows, true) });
+        self.finish();
+        result
+    }
+
+    /// step と同じだが、Neighbors では上下の帯を待つ前に内部行の BandView で f を呼び、
+    /// 待った後で残りの行（帯の上端・下端のハロー幅の行）の BandView で f を呼ぶ。
+    /// Barrier では帯全体の BandView で1回だけ呼ぶ
+    pub fn step_overlapped(&mut self, mut f: impl FnMut(BandView<'_>)) {
+        let rows = self.rows();
+        if self.grid.barrier.is_some() {
+            self.step(f);
+            return;
+        }
+        let halo = self.grid.halo;
+        let top_end = if self.id > 0 { (rows.start + halo).min(rows.end) } else { rows.start };
+        let bottom_start = if self.id + 1 < self.grid.bands.len() { rows.end.saturating_sub(halo).max(top_end) } else { rows.end };
+
+        // SAFETY: 内部行は上下の帯のハローに入らず、読むのは自分の帯の行だけ
+        f(unsafe { self.view(top_end..bottom_start, false) });
+        self.wait_neighbors();
+        // SAFETY: 待った後なので、上端・下端の行も書いてよく、ハローも読める
+        f(unsafe { self.view(rows.start..top_end, true) });
+        f(unsafe { self.view(bottom_start..rows.end, true) });
+        self.finish();
+    }
+
+    // Neighbors: 上下の帯が前のステップを終えるまで待つ
+    fn wait_neighbors(&mut self) {
+        if self.grid.barrier.is_none() {
+            let counters = &self.grid.counters;
+            if self.id > 0 {
+                counters[self.id - 1].wait_counted(self.step, self.stats.as_mut());
+            }
+            if let Some(below) = counters.get(self.id + 1) {
+                below.wait_counted(self.step, self.stats.as_mut());
+            }
+        }
+    }
+
+    // ステップの完了を知らせる（Barrier では全帯がここに揃うまで待つ）
+    fn finish(&mut self) {
+        let done = self.step + 1;
+        match &self.grid.barrier {
+            Some(barrier) => {
+                barrier.wait();
+            }
+            None => {
+                self.grid.counters[self.id].publish(done);
+            }
+        }
+        self.step = done;
+    }
+
+    // rows（帯の担当行の一部）に書く BandView。halo が false なら自分の帯の行しか読めない（外を読めば panic）
+    //
+    // SAFETY: rows が上下の帯から読まれる行を含む場合と、halo が true の場合は、wait_neighbors の後で呼ぶこと
+    unsafe fn view(&self, rows: Range<usize>, halo: bool) -> BandView<'_> {
+        let grid = self.grid;
+        let band = self.rows();
+        let src_rows = if halo { band.start.saturating_sub(grid.halo)..(band.end + grid.halo).min(N) } else { band };
+        let src_buffer = grid.buffers[self.step % 2].as_ptr();
+        let dst_buffer = grid.buffers[(self.step + 1) % 2].as_ptr();
+
+        // SAFETY: 範囲はどちらも格子（N*M 要素）の中。説明のとおり、この step の間は
+        // src_rows を誰も書かず、dst の rows はこの帯だけが触る。トークンは帯ごとに1つで、&mut self で直列化される
+        unsafe {
+            BandView {
+                rows: rows.clone(),
+                halo: grid.halo,
+                src_first_row: src_rows.start,
+                src: std::slice::from_raw_parts(src_buffer.add(src_rows.start * M), src_rows.len() * M),
+                dst: std::slice::from_raw_parts_mut(dst_buffer.add(rows.start * M), rows.len() * M),
+            }
+        }
+    }
+}
+
+/// 1ステップの間だけ借りる、帯の読み書きの範囲
+pub struct BandView<'v> {
+    rows: Range<usize>,
+    halo: usize,
+    src_first_row: usize,
+    src: &'v [f64],
+    dst: &'v mut [f64],
+}
+
+impl BandView<'_> {
+    /// 書き込める行（全体の行番号）
+    pub fn rows(&self) -> Range<usize> {
+        self.rows.clone()
+    }
+
+    /// 全体の i 行目について (上の行, i 行目, 下の行, 書き込み先の i 行目)
+    ///
+    /// i は内部行（1 ≤ i < N-1）かつ rows の中であること（そうでなければ panic）。
+    #[inline(always)]
+    pub fn stencil_rows(&mut self, i: usize) -> (&[f64], &[f64], &[f64], &mut [f64]) {
+        let s = (i - self.src_first_row) * M;
+        let d = (i - self.rows.start) * M;
+        (&self.src[s - M..s], &self.src[s..s + M], &self.src[s + M..s + 2 * M], &mut self.dst[d..d + M])
+    }
+
+    /// 帯の担当行を stencil で計算し、熱源を固定する。S::RADIUS はハローの行数以下であること
+    #[inline(always)]
+    pub fn apply<S: Stencil>(&mut self, stencil: &S) {
+        assert!(S::RADIUS <= self.halo, "stencil radius exceeds the halo");
+        let rows = self.rows();
+        for i in rows.start.max(S::RADIUS)..rows.end.min(N - S::RADIUS) {
+            let first = self.src_first_row;
+            let src = self.src;
+            let d = (i - rows.start) * M;
+            // SAFETY: row(k) は src をスライスで切り出すので M 要素（範囲外なら panic）、dst の行も M 要素
+            unsafe {
+                stencil.apply_row_unchecked(i, |k| &src[(k - first) * M..(k - first + 1) * M], &mut self.dst[d..d + M]);
+            }
+        }
+        if rows.contains(&(N / 2)) {
+            self.set(N / 2, M / 2, 100.0);
+        }
+    }
+
+    /// 全体の (i, j) のセルに書き込む
+    pub fn set(&mut self, i: usize, j: usize, value: f64) {
+        self.dst[(i - self.rows.start) * M + j] = value;
+    }
+}
+
+/// 生ポインタをスレッド間で渡すためのラッパー
+///
+/// 3次元のスラブ分割や台形分割など、SharedGrid の形（帯ごとに1ステップずつ同期）に収まらない実装が使う。
+/// 書き込む範囲が重ならないことと、読む前に相手の書き込みが終わっていることは使う側が保証する。
+#[derive(Debug)]
+pub struct SharedPtr<T>(*mut T);
+
+// SAFETY: ポインタを渡すだけで、参照外しは使う側の unsafe ブロックで行う
+unsafe impl<T> Send for SharedPtr<T> {}
+unsafe impl<T> Sync for SharedPtr<T> {}
+
+impl<T> Clone for SharedPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SharedPtr<T> {}
+
+impl<T> SharedPtr<T> {
+    pub fn new(ptr: *mut T) -> Self {
+        SharedPtr(ptr)
+    }
+
+    #[inline(always)]
+    pub fn get(self) -> *mut T {
+        self.0
+    }
+}
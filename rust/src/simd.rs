@@ -0,0 +1,242 @@
+use std::sync::OnceLock;
+
+/*
+  std::arch による手動ベクトル化の行カーネル
+
+  target-cpu=native に頼らず、実行時に CPU の機能を調べて SSE2 / AVX2 / AVX-512 のカーネルを選ぶ。
+  既定のビルドはどの x86-64 CPU でも動き、ビルドしたマシン専用に最適化するときだけ
+  RUSTFLAGS="-C target-cpu=native" cargo build --release
+  のように明示的に指定する（README の「Rust版のみ」を参照）。
+
+  演算順序はスカラー版（jacobi_step）と同じ v + (fx·(上 + 下 - 2v) + fy·(左 + 右 - 2v)) で、
+  FMA も使わないため、どのレベルでも結果はスカラー版とビット単位で一致する。
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimdLevel {
+    Scalar,
+    Sse2,
+    Avx2,
+    Avx512,
+}
+
+impl SimdLevel {
+    pub const ALL: [SimdLevel; 4] = [SimdLevel::Scalar, SimdLevel::Sse2, SimdLevel::Avx2, SimdLevel::Avx512];
+
+    /// この CPU で使える最も幅の広いレベル（初回の呼び出しで判定してキャッシュする）
+    pub fn detect() -> SimdLevel {
+        static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+        *LEVEL.get_or_init(|| {
+            SimdLevel::ALL
+                .into_iter()
+                .rev()
+                .find(|level| level.is_supported())
+                .unwrap_or(SimdLevel::Scalar)
+        })
+    }
+
+    pub fn is_supported(&self) -> bool {
+        match self {
+            SimdLevel::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx512 => is_x86_feature_detected!("avx512f"),
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => false,
+        }
+    }
+
+    /// この CPU で使えるレベルの一覧（ベンチマーク用）
+    pub fn available() -> Vec<SimdLevel> {
+        SimdLevel::ALL.into_iter().filter(|level| level.is_supported()).collect()
+    }
+
+    /// 1命令で処理する f64 の数
+    pub fn lanes(&self) -> usize {
+        match self {
+            SimdLevel::Scalar => 1,
+            SimdLevel::Sse2 => 2,
+            SimdLevel::Avx2 => 4,
+            SimdLevel::Avx512 => 8,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SimdLevel::Scalar => "Scalar",
+            SimdLevel::Sse2 => "SSE2",
+            SimdLevel::Avx2 => "AVX2",
+            SimdLevel::Avx512 => "AVX-512",
+        }
+    }
+}
+
+/// この CPU で使えることを確認済みの SimdLevel
+///
+/// CPU の機能の確認（is_supported）はソルバーの呼び出しごとに require で1回だけ行い、
+/// 行ごとの update_row ではこの値を渡すだけにする。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimdKernel {
+    level: SimdLevel,
+}
+
+impl SimdKernel {
+    /// level がこの CPU で使えなければ None
+    pub fn new(level: SimdLevel) -> Option<SimdKernel> {
+        level.is_supported().then_some(SimdKernel { level })
+    }
+
+    /// level がこの CPU で使えなければ panic する
+    pub fn require(level: SimdLevel) -> SimdKernel {
+        SimdKernel::new(level).unwrap_or_else(|| panic!("{} is not supported on this CPU", level.label()))
+    }
+
+    pub fn level(&self) -> SimdLevel {
+        self.level
+    }
+}
+
+/// 1行分の更新: dst[j] (1 ≤ j < len-1) を up / cur / down の3行から計算する
+///
+/// dst[0] と dst[len-1]（境界列）は書き換えない。
+#[inline(always)]
+pub fn update_row(kernel: SimdKernel, up: &[f64], cur: &[f64], down: &[f64], dst: &mut [f64], factor_x: f64, factor_y: f64) {
+    let len = cur.len();
+    assert!(up.len() == len && down.len() == len && dst.len() == len, "row length mismatch");
+
+    // SAFETY: 4行とも len 要素あることを確認済み。kernel のレベルはこの CPU で使える
+    unsafe {
+        update_row_raw(kernel.level(), up.as_ptr(), cur.as_ptr(), down.as_ptr(), dst.as_mut_ptr(), len, factor_x, factor_y);
+    }
+}
+
+/// update_row の生ポインタ版
+///
+/// # Safety
+/// up / cur / down は len 要素の読み出し、dst は len 要素の書き込みが可能であること。
+/// dst の範囲は up / cur / down と重ならないこと。level はこの CPU で使えること
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub unsafe fn update_row_raw(
+    level: SimdLevel,
+    up: *const f64,
+    cur: *const f64,
+    down: *const f64,
+    dst: *mut f64,
+    len: usize,
+    factor_x: f64,
+    factor_y: f64,
+) {
+    if len < 3 {
+        return;
+    }
+    unsafe {
+        match level {
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Sse2 => x86::row_sse2(up, cur, down, dst, len, factor_x, factor_y),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => x86::row_avx2(up, cur, down, dst, len, factor_x, factor_y),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx512 => x86::row_avx512(up, cur, down, dst, len, factor_x, factor_y),
+            _ => row_scalar(up, cur, down, dst, 1, len - 1, factor_x, factor_y),
+        }
+    }
+}
+
+// 列 [j0, j1) をスカラーで計算する（SIMD版の端数処理にも使う）
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+unsafe fn row_scalar(
+    up: *const f64,
+    cur: *const f64,
+    down: *const f64,
+    dst: *mut f64,
+    j0: usize,
+    j1: usize,
+    factor_x: f64,
+    factor_y: f64,
+) {
+    for j in j0..j1 {
+        unsafe {
+            let v = *cur.add(j);
+            let lap_x = *up.add(j) + *down.add(j) - 2.0 * v;
+            let lap_y = *cur.add(j - 1) + *cur.add(j + 1) - 2.0 * v;
+            *dst.add(j) = v + (factor_x * lap_x + factor_y * lap_y);
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+    use super::row_scalar;
+
+    // 3つの版は命令の幅が違うだけで、同じ手順で計算する。
+    // 読み込みは非アラインのロードを使う（左右の隣接は1要素ずれるため、アラインを揃えられない）
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn row_sse2(up: *const f64, cur: *const f64, down: *const f64, dst: *mut f64, len: usize, factor_x: f64, factor_y: f64) {
+        let fx = _mm_set1_pd(factor_x);
+        let fy = _mm_set1_pd(factor_y);
+        let two = _mm_set1_pd(2.0);
+
+        let mut j = 1;
+        while j + 2 < len {
+            unsafe {
+                let v = _mm_loadu_pd(cur.add(j));
+                let two_v = _mm_mul_pd(two, v);
+                let lap_x = _mm_sub_pd(_mm_add_pd(_mm_loadu_pd(up.add(j)), _mm_loadu_pd(down.add(j))), two_v);
+                let lap_y = _mm_sub_pd(_mm_add_pd(_mm_loadu_pd(cur.add(j - 1)), _mm_loadu_pd(cur.add(j + 1))), two_v);
+                let flux = _mm_add_pd(_mm_mul_pd(fx, lap_x), _mm_mul_pd(fy, lap_y));
+                _mm_storeu_pd(dst.add(j), _mm_add_pd(v, flux));
+            }
+            j += 2;
+        }
+        unsafe { row_scalar(up, cur, down, dst, j, len - 1, factor_x, factor_y) };
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn row_avx2(up: *const f64, cur: *const f64, down: *const f64, dst: *mut f64, len: usize, factor_x: f64, factor_y: f64) {
+        let fx = _mm256_set1_pd(factor_x);
+        let fy = _mm256_set1_pd(factor_y);
+        let two = _mm256_set1_pd(2.0);
+
+        let mut j = 1;
+        while j + 4 < len {
+            unsafe {
+                let v = _mm256_loadu_pd(cur.add(j));
+                let two_v = _mm256_mul_pd(two, v);
+                let lap_x = _mm256_sub_pd(_mm256_add_pd(_mm256_loadu_pd(up.add(j)), _mm256_loadu_pd(down.add(j))), two_v);
+                let lap_y = _mm256_sub_pd(_mm256_add_pd(_mm256_loadu_pd(cur.add(j - 1)), _mm256_loadu_pd(cur.add(j + 1))), two_v);
+                let flux = _mm256_add_pd(_mm256_mul_pd(fx, lap_x), _mm256_mul_pd(fy, lap_y));
+                _mm256_storeu_pd(dst.add(j), _mm256_add_pd(v, flux));
+            }
+            j += 4;
+        }
+        unsafe { row_scalar(up, cur, down, dst, j, len - 1, factor_x, factor_y) };
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn row_avx512(up: *const f64, cur: *const f64, down: *const f64, dst: *mut f64, len: usize, factor_x: f64, factor_y: f64) {
+        let fx = _mm512_set1_pd(factor_x);
+        let fy = _mm512_set1_pd(factor_y);
+        let two = _mm512_set1_pd(2.0);
+
+        let mut j = 1;
+        while j + 8 < len {
+            unsafe {
+                let v = _mm512_loadu_pd(cur.add(j));
+                let two_v = _mm512_mul_pd(two, v);
+                let lap_x = _mm512_sub_pd(_mm512_add_pd(_mm512_loadu_pd(up.add(j)), _mm512_loadu_pd(down.add(j))), two_v);
+                let lap_y = _mm512_sub_pd(_mm512_add_pd(_mm512_loadu_pd(cur.add(j - 1)), _mm512_loadu_pd(cur.add(j + 1))), two_v);
+                let flux = _mm512_add_pd(_mm512_mul_pd(fx, lap_x), _mm512_mul_pd(fy, lap_y));
+                _mm512_storeu_pd(dst.add(j), _mm512_add_pd(v, flux));
+            }
+            j += 8;
+        }
+        unsafe { row_scalar(up, cur, down, dst, j, len - 1, factor_x, factor_y) };
+    }
+}
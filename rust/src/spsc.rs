@@ -0,0 +1,122 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use crate::sync::WaitStrategy;
+
+/*
+  1対1（単一の送り手と単一の受け手）の有界リングバッファ
+
+  帯分割の境界行を隣接スレッドに送るための固定長の行（row_len 要素）のキュー。
+  スロットは生成時に確保し、送るたびにスロットへ行をコピーするため、mpsc と違って送信ごとの確保が無い。
+    - tail: 送り手が書き込んだ行数（送り手だけが書き込む）
+    - head: 受け手が読み終えた行数（受け手だけが書き込む）
+  tail - head が capacity なら満杯で送り手が待ち、0 なら空で受け手が待つ（待ち方は WaitStrategy の既定）。
+  相手側が drop された（スレッドが panic した）場合は、待ち続けずに Err を返す。
+*/
+
+#[repr(align(64))]
+struct Padded<T>(T);
+
+struct Ring {
+    slots: Box<[UnsafeCell<f64>]>,
+    capacity: usize,
+    row_len: usize,
+    head: Padded<AtomicUsize>,
+    tail: Padded<AtomicUsize>,
+    closed: AtomicBool,
+}
+
+// スロットは tail / head の受け渡しで、同時にはどちらか一方だけが触る
+unsafe impl Sync for Ring {}
+
+/// 相手側が drop された
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Disconnected;
+
+pub struct RowProducer {
+    ring: Arc<Ring>,
+}
+
+pub struct RowConsumer {
+    ring: Arc<Ring>,
+}
+
+/// capacity 行まで溜められる、1行 row_len 要素のリングバッファ
+pub fn row_ring(capacity: usize, row_len: usize) -> (RowProducer, RowConsumer) {
+    let capacity = capacity.max(1);
+    let ring = Arc::new(Ring {
+        slots: (0..capacity * row_len).map(|_| UnsafeCell::new(0.0)).collect(),
+        capacity,
+        row_len,
+        head: Padded(AtomicUsize::new(0)),
+        tail: Padded(AtomicUsize::new(0)),
+        closed: AtomicBool::new(false),
+    });
+    (RowProducer { ring: ring.clone() }, RowConsumer { ring })
+}
+
+impl Ring {
+    // index 番目の行のスロットの先頭（UnsafeCell<f64> は f64 と同じ配置なので、スライス全体から位置を求める）
+    fn slot(&self, index: usize) -> *mut f64 {
+        UnsafeCell::raw_get(self.slots.as_ptr()).wrapping_add((index % self.capacity) * self.row_len)
+    }
+}
+
+impl RowProducer {
+    /// row を送る（満杯なら受け手が読むまで待つ）
+    pub fn push(&mut self, row: &[f64]) -> Result<(), Disconnected> {
+        let ring = &*self.ring;
+        assert_eq!(row.len(), ring.row_len);
+        let tail = ring.tail.0.load(Ordering::Relaxed);
+
+        let mut closed = false;
+        WaitStrategy::default().wait_until(|| {
+            closed = ring.closed.load(Ordering::Relaxed);
+            closed || tail - ring.head.0.load(Ordering::Acquire) < ring.capacity
+        });
+        if closed {
+            return Err(Disconnected);
+        }
+
+        // SAFETY: このスロットは受け手が読み終えており（head が進んでいる）、tail を進めるまで受け手は触らない
+        unsafe { std::ptr::copy_nonoverlapping(row.as_ptr(), ring.slot(tail), ring.row_len) };
+        ring.tail.0.store(tail + 1, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl RowConsumer {
+    /// 次の行を dst に受け取る（空なら送り手が書くまで待つ）
+    pub fn pop_into(&mut self, dst: &mut [f64]) -> Result<(), Disconnected> {
+        let ring = &*self.ring;
+        assert_eq!(dst.len(), ring.row_len);
+        let head = ring.head.0.load(Ordering::Relaxed);
+
+        let mut ready = false;
+        WaitStrategy::default().wait_until(|| {
+            ready = ring.tail.0.load(Ordering::Acquire) > head;
+            ready || ring.closed.load(Ordering::Acquire)
+        });
+        // 送り手が drop する前に送った行は、drop の後でも受け取れる
+        if !ready && ring.tail.0.load(Ordering::Acquire) <= head {
+            return Err(Disconnected);
+        }
+
+        // SAFETY: このスロットは送り手が書き終えており（tail が進んでいる）、head を進めるまで送り手は触らない
+        unsafe { std::ptr::copy_nonoverlapping(ring.slot(head), dst.as_mut_ptr(), ring.row_len) };
+        ring.head.0.store(head + 1, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl Drop for RowProducer {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl Drop for RowConsumer {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
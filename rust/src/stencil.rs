@@ -0,0 +1,150 @@
+use crate::grid::{explicit_dt_limit, ALPHA, DT, DX, DY, N, M};
+
+/*
+  差し替え可能なステンシル（1行分の更新式）
+
+  Stencil::apply_row は全体の i 行目の更新値 u + α·Δt·∇²u を dst に書く。各実装（single, rayon, barrier, ...）の
+  本体はステンシルを引数に取る1つだけにし、ループ構造や並列化・境界交換はそちらが受け持つ。
+  行は row(k)（全体の k 行目、i - RADIUS ≤ k ≤ i + RADIUS）で受け取るため、帯分割の実装は
+  隣の帯から受け取ったゴースト行をコピーせずにそのまま渡せる。
+
+  RADIUS は片側に必要な隣接セルの数。外周から RADIUS セル分は固定値（0）とし、
+  帯分割の実装では上下の帯と RADIUS 行ずつゴースト行を交換する。
+    - FivePoint   : 従来の5点ステンシル（RADIUS = 1）
+    - NinePoint   : 斜め隣接も使う9点ステンシル（RADIUS = 1。Δx = Δy なら等方的な誤差になる）
+    - FourthOrder : 各方向に ±2 セルを使う4次精度の13点ステンシル（RADIUS = 2）
+    - Diffusivity : 可変拡散係数の5点ステンシル（diffusivity.rs、RADIUS = 1）
+*/
+
+pub trait Stencil: Sync {
+    /// 片側に必要な隣接セル数（= 帯分割で交換するゴースト行の数）
+    const RADIUS: usize;
+    const NAME: &'static str;
+
+    /// 全体の i 行目（RADIUS ≤ i < N - RADIUS）の列 RADIUS..M-RADIUS を dst に書く
+    fn apply_row<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]);
+
+    /// apply_row の境界チェックなし版（unsafe 実装から使う）。既定では apply_row と同じ
+    ///
+    /// # Safety
+    /// row(k) はどれも M 要素、dst も M 要素で、i は RADIUS ≤ i < N - RADIUS であること
+    #[inline(always)]
+    unsafe fn apply_row_unchecked<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        self.apply_row(i, row, dst);
+    }
+
+    /// 陽解法が安定となる時間刻みの上限
+    fn dt_limit(&self) -> f64;
+
+    /// 固定値のセルか（外周 RADIUS セル分と中心の熱源）
+    #[inline(always)]
+    fn is_fixed(i: usize, j: usize) -> bool {
+        i < Self::RADIUS
+            || i >= N - Self::RADIUS
+            || j < Self::RADIUS
+            || j >= M - Self::RADIUS
+            || (i == N / 2 && j == M / 2)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FivePoint;
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NinePoint;
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FourthOrder;
+
+impl Stencil for FivePoint {
+    const RADIUS: usize = 1;
+    const NAME: &'static str = "5-point";
+
+    #[inline(always)]
+    fn apply_row<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        let factor_x = ALPHA * DT / (DX * DX);
+        let factor_y = ALPHA * DT / (DY * DY);
+        let (up, cur, down) = (row(i - 1), row(i), row(i + 1));
+        for j in 1..M - 1 {
+            let v = cur[j];
+            let lap_x = up[j] + down[j] - 2.0 * v;
+            let lap_y = cur[j - 1] + cur[j + 1] - 2.0 * v;
+            dst[j] = v + (factor_x * lap_x + factor_y * lap_y);
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn apply_row_unchecked<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        let factor_x = ALPHA * DT / (DX * DX);
+        let factor_y = ALPHA * DT / (DY * DY);
+        let (up, cur, down) = (row(i - 1), row(i), row(i + 1));
+        // SAFETY: 各行は M 要素で、j は 1..M-1
+        unsafe {
+            for j in 1..M - 1 {
+                let v = *cur.get_unchecked(j);
+                let lap_x = *up.get_unchecked(j) + *down.get_unchecked(j) - 2.0 * v;
+                let lap_y = *cur.get_unchecked(j - 1) + *cur.get_unchecked(j + 1) - 2.0 * v;
+                *dst.get_unchecked_mut(j) = v + (factor_x * lap_x + factor_y * lap_y);
+            }
+        }
+    }
+
+    fn dt_limit(&self) -> f64 {
+        explicit_dt_limit()
+    }
+}
+
+impl Stencil for NinePoint {
+    const RADIUS: usize = 1;
+    const NAME: &'static str = "9-point";
+
+    // ∇²u ≈ δx²u/Δx² + δy²u/Δy² + (Δx² + Δy²)/12 · δx²δy²u/(Δx²Δy²)
+    // Δx = Δy = h なら従来の [4·(上下左右) + (斜め4点) - 20·u] / (6h²) になる
+    #[inline(always)]
+    fn apply_row<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        let factor_x = ALPHA * DT / (DX * DX);
+        let factor_y = ALPHA * DT / (DY * DY);
+        let factor_xy = (factor_x + factor_y) / 12.0;
+        let (up, cur, down) = (row(i - 1), row(i), row(i + 1));
+        for j in 1..M - 1 {
+            let v = cur[j];
+            let lap_x = up[j] + down[j] - 2.0 * v;
+            let lap_y = cur[j - 1] + cur[j + 1] - 2.0 * v;
+            // δx²δy²u = (斜め4点) - 2·(上下左右) + 4·u
+            let edges = up[j] + down[j] + cur[j - 1] + cur[j + 1];
+            let corners = up[j - 1] + up[j + 1] + down[j - 1] + down[j + 1];
+            let cross = corners - 2.0 * edges + 4.0 * v;
+            dst[j] = v + (factor_x * lap_x + factor_y * lap_y + factor_xy * cross);
+        }
+    }
+
+    // 固有値は (δx², δy²) の固有値 (-a, -b)（a, b ∈ [0, 4]）について双線形なので、端点で最小になる。
+    // 最小値は min(-4/Δx², -4/Δy², -(8/3)(1/Δx² + 1/Δy²))
+    fn dt_limit(&self) -> f64 {
+        let (ix, iy) = (1.0 / (DX * DX), 1.0 / (DY * DY));
+        let lambda = (4.0 * ix).max(4.0 * iy).max(8.0 / 3.0 * (ix + iy));
+        2.0 / (ALPHA * lambda)
+    }
+}
+
+impl Stencil for FourthOrder {
+    const RADIUS: usize = 2;
+    const NAME: &'static str = "13-point 4th order";
+
+    // 各方向 ∂²u ≈ (-u[-2] + 16u[-1] - 30u + 16u[+1] - u[+2]) / (12Δ²)
+    #[inline(always)]
+    fn apply_row<'r>(&self, i: usize, row: impl Fn(usize) -> &'r [f64], dst: &mut [f64]) {
+        let factor_x = ALPHA * DT / (DX * DX);
+        let factor_y = ALPHA * DT / (DY * DY);
+        let (up2, up, cur, down, down2) = (row(i - 2), row(i - 1), row(i), row(i + 1), row(i + 2));
+        for j in 2..M - 2 {
+            let v = cur[j];
+            let lap_x = 16.0 * (up[j] + down[j]) - (up2[j] + down2[j]) - 30.0 * v;
+            let lap_y = 16.0 * (cur[j - 1] + cur[j + 1]) - (cur[j - 2] + cur[j + 2]) - 30.0 * v;
+            dst[j] = v + (factor_x * lap_x + factor_y * lap_y) / 12.0;
+        }
+    }
+
+    // 各方向の固有値の最小値は -16/3 / Δ²
+    fn dt_limit(&self) -> f64 {
+        3.0 / (8.0 * ALPHA * (1.0 / (DX * DX) + 1.0 / (DY * DY)))
+    }
+}
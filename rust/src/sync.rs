@@ -0,0 +1,367 @@
+use std::sync::atomic::{fence, AtomicU32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/*
+  スピン型の同期で、相手の進み（ステップ数）を待つ方法
+
+  atomic_counter 系の実装は、相手のカウンタが目的のステップに達するまで待つ。
+  待ち方によって、待ち時間（起きるまでの遅延）と待っている間の CPU 消費が変わる。
+  スレッド数がコア数を超える（オーバーサブスクライブ）と、スピンは相手の実行時間を奪ってしまう。
+    - Spin                  : ひたすらスピンする。遅延は最小だが CPU を使い続ける
+    - SpinYield { spins }   : spins 回スピンしたら yield_now する（従来の wait_for_step は spins = 256）
+    - Backoff { max_spins } : スピン回数を 1, 2, 4, ... と倍にし、max_spins に達したら毎回 yield_now する
+    - SpinPark { spins }    : spins 回スピンしたら眠り、相手の publish で起こされる
+                              （Linux は futex、それ以外は thread::park）
+  眠った相手を起こすため、カウンタは StepCounter を使い、ステップの完了は publish で知らせる。
+
+  カウンタの型は StepSignal で差し替える。待ち方を選ばない既定の atomic_counter / unsafe_atomic_counter は、
+  従来どおりの SpinCounter / AlignedSpinCounter（SpinYield { spins: 256 } で待つ AtomicUsize）を使い、
+  StepCounter（64バイト境界・futex 付き）は待ち方を選ぶ *_with 版だけが使う。
+*/
+
+/// 完了したステップ数を知らせ、相手の完了を待つカウンタ
+pub trait StepSignal: Send + Sync {
+    /// ステップ step の完了を知らせる
+    fn publish(&self, step: usize);
+
+    /// カウンタが step 以上になるまで待つ（戻った後は、publish より前の書き込みが見える）
+    fn wait_for(&self, step: usize);
+
+    fn load(&self) -> usize;
+
+    /// wait_for と同じだが、stats があれば待機を記録する
+    #[inline(always)]
+    fn wait_counted(&self, step: usize, stats: Option<&mut WaitStats>)
+    where
+        Self: Sized,
+    {
+        match stats {
+            Some(stats) => stats.wait_for(self, step),
+            None => self.wait_for(step),
+        }
+    }
+}
+
+/// 従来の atomic_counter のカウンタ。AtomicUsize そのもので、パディングも眠る仕組みも無い
+pub struct SpinCounter(AtomicUsize);
+
+impl SpinCounter {
+    pub fn new() -> Self {
+        SpinCounter(AtomicUsize::new(0))
+    }
+}
+
+impl Default for SpinCounter {
+    fn default() -> Self {
+        SpinCounter::new()
+    }
+}
+
+impl StepSignal for SpinCounter {
+    #[inline(always)]
+    fn publish(&self, step: usize) {
+        self.0.store(step, Ordering::Release);
+    }
+
+    #[inline(always)]
+    fn wait_for(&self, step: usize) {
+        WaitStrategy::default().wait_until(|| self.0.load(Ordering::Relaxed) >= step);
+        fence(Ordering::Acquire);
+    }
+
+    fn load(&self) -> usize {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// 従来の unsafe_atomic_counter のカウンタ。SpinCounter を64バイト境界に置き、隣のカウンタとキャッシュラインを共有しない
+#[repr(align(64))]
+#[derive(Default)]
+pub struct AlignedSpinCounter(SpinCounter);
+
+impl AlignedSpinCounter {
+    pub fn new() -> Self {
+        AlignedSpinCounter(SpinCounter::new())
+    }
+}
+
+impl StepSignal for AlignedSpinCounter {
+    #[inline(always)]
+    fn publish(&self, step: usize) {
+        self.0.publish(step);
+    }
+
+    #[inline(always)]
+    fn wait_for(&self, step: usize) {
+        self.0.wait_for(step);
+    }
+
+    fn load(&self) -> usize {
+        self.0.load()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitStrategy {
+    Spin,
+    SpinYield { spins: u32 },
+    Backoff { max_spins: u32 },
+    SpinPark { spins: u32 },
+}
+
+impl Default for WaitStrategy {
+    fn default() -> Self {
+        WaitStrategy::SpinYield { spins: 256 }
+    }
+}
+
+impl WaitStrategy {
+    /// ベンチマークで比較する設定
+    pub const PRESETS: [WaitStrategy; 6] = [
+        WaitStrategy::Spin,
+        WaitStrategy::SpinYield { spins: 256 },
+        WaitStrategy::SpinYield { spins: 16 },
+        WaitStrategy::Backoff { max_spins: 1024 },
+        WaitStrategy::SpinPark { spins: 256 },
+        WaitStrategy::SpinPark { spins: 0 },
+    ];
+
+    /// "spin" / "yield:256" / "backoff:1024" / "park:256"（parse で読み戻せる）
+    pub fn label(&self) -> String {
+        match self {
+            WaitStrategy::Spin => "spin".to_string(),
+            WaitStrategy::SpinYield { spins } => format!("yield:{spins}"),
+            WaitStrategy::Backoff { max_spins } => format!("backoff:{max_spins}"),
+            WaitStrategy::SpinPark { spins } => format!("park:{spins}"),
+        }
+    }
+
+    /// 回数を省略した場合は PRESETS の最初の設定と同じ回数になる
+    pub fn parse(text: &str) -> Result<WaitStrategy, String> {
+        let text = text.trim().to_ascii_lowercase();
+        let (name, count) = match text.split_once(':') {
+            Some((name, count)) => (name, Some(count.parse::<u32>().map_err(|_| format!("invalid spin count: {text}"))?)),
+            None => (text.as_str(), None),
+        };
+        match name {
+            "spin" if count.is_none() => Ok(WaitStrategy::Spin),
+            "yield" => Ok(WaitStrategy::SpinYield { spins: count.unwrap_or(256) }),
+            "backoff" => Ok(WaitStrategy::Backoff { max_spins: count.unwrap_or(1024) }),
+            "park" => Ok(WaitStrategy::SpinPark { spins: count.unwrap_or(256) }),
+            _ => Err(format!("unknown wait strategy: {text}")),
+        }
+    }
+
+    /// ready() が true になるまで待つ（起こしてくれる相手がいないので、SpinPark は SpinYield と同じ）
+    pub fn wait_until(&self, mut ready: impl FnMut() -> bool) {
+        match *self {
+            WaitStrategy::Spin => {
+                while !ready() {
+                    std::hint::spin_loop();
+                }
+            }
+            WaitStrategy::SpinYield { spins } | WaitStrategy::SpinPark { spins } => {
+                let mut spin = 0;
+                while !ready() {
+                    std::hint::spin_loop();
+                    spin += 1;
+                    if spin >= spins {
+                        spin = 0;
+                        std::thread::yield_now();
+                    }
+                }
+            }
+            WaitStrategy::Backoff { max_spins } => {
+                let mut round = 1;
+                while !ready() {
+                    for _ in 0..round {
+                        std::hint::spin_loop();
+                    }
+                    if round >= max_spins {
+                        std::thread::yield_now();
+                    } else {
+                        round *= 2;
+                    }
+                }
+            }
+        }
+    }
+
+    // 眠る前にスピンする回数（SpinPark 以外は眠らない）
+    fn park_after(&self) -> Option<u32> {
+        match *self {
+            WaitStrategy::SpinPark { spins } => Some(spins),
+            _ => None,
+        }
+    }
+}
+
+/// 完了したステップ数を知らせるカウンタ（待ち方は生成時に決める）
+///
+/// 書き込むのは担当スレッドだけで、他のスレッドは wait_for で目的のステップまで待つ。
+/// futex が 32 ビットの値しか扱えないため、ステップ数は u32 の範囲に収めること。
+#[repr(align(64))]
+pub struct StepCounter {
+    value: AtomicU32,
+    sleepers: AtomicU32, // 眠っている（眠ろうとしている）スレッドの数
+    strategy: WaitStrategy,
+    #[cfg(not(target_os = "linux"))]
+    parked: std::sync::Mutex<Vec<std::thread::Thread>>,
+}
+
+impl StepCounter {
+    pub fn new(strategy: WaitStrategy) -> Self {
+        StepCounter {
+            value: AtomicU32::new(0),
+            sleepers: AtomicU32::new(0),
+            strategy,
+            #[cfg(not(target_os = "linux"))]
+            parked: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn strategy(&self) -> WaitStrategy {
+        self.strategy
+    }
+
+    pub fn load(&self) -> usize {
+        self.value.load(Ordering::Acquire) as usize
+    }
+
+    /// 0 に戻す（誰も待っていないときに呼ぶ）
+    pub fn reset(&self) {
+        self.value.store(0, Ordering::Relaxed);
+    }
+
+    /// ステップ step の完了を知らせ、眠っている相手がいれば起こす
+    #[inline(always)]
+    pub fn publish(&self, step: usize) {
+        self.value.store(step as u32, Ordering::Release);
+        if self.strategy.park_after().is_some() {
+            // 待つ側の「sleepers を増やしてから value を読む」と対になる。
+            // どちらかが必ず相手の書き込みを見るため、起こし損ねない
+            fence(Ordering::SeqCst);
+            if self.sleepers.load(Ordering::Relaxed) > 0 {
+                self.wake_all();
+            }
+        }
+    }
+
+    /// カウンタが step 以上になるまで待つ
+    #[inline(always)]
+    pub fn wait_for(&self, step: usize) {
+        let step = step as u32;
+        let reached = || self.value.load(Ordering::Relaxed) >= step;
+
+        match self.strategy.park_after() {
+            None => self.strategy.wait_until(reached),
+            Some(spins) => {
+                for _ in 0..spins {
+                    if reached() {
+                        fence(Ordering::Acquire);
+                        return;
+                    }
+                    std::hint::spin_loop();
+                }
+                loop {
+                    self.sleepers.fetch_add(1, Ordering::SeqCst);
+                    let current = self.value.load(Ordering::SeqCst);
+                    if current < step {
+                        self.sleep(current);
+                    }
+                    self.sleepers.fetch_sub(1, Ordering::Relaxed);
+                    if reached() {
+                        break;
+                    }
+                }
+            }
+        }
+        fence(Ordering::Acquire);
+    }
+
+    // value がまだ current なら眠る（起こされたか値が変わっていれば戻る）
+    #[cfg(target_os = "linux")]
+    fn sleep(&self, current: u32) {
+        // SAFETY: value は self が生きている間有効な 32 ビットの整数
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                self.value.as_ptr(),
+                libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+                current,
+                std::ptr::null::<libc::timespec>(),
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wake_all(&self) {
+        // SAFETY: sleep と同じアドレスで待っているスレッドを全員起こす
+        unsafe {
+            libc::syscall(libc::SYS_futex, self.value.as_ptr(), libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG, i32::MAX);
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sleep(&self, current: u32) {
+        self.parked.lock().unwrap().push(std::thread::current());
+        // 登録した後に値が変わっていれば、unpark を待たずに戻る
+        if self.value.load(Ordering::SeqCst) == current {
+            std::thread::park();
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn wake_all(&self) {
+        for thread in self.parked.lock().unwrap().drain(..) {
+            thread.unpark();
+        }
+    }
+}
+
+impl StepSignal for StepCounter {
+    #[inline(always)]
+    fn publish(&self, step: usize) {
+        StepCounter::publish(self, step);
+    }
+
+    #[inline(always)]
+    fn wait_for(&self, step: usize) {
+        StepCounter::wait_for(self, step);
+    }
+
+    fn load(&self) -> usize {
+        StepCounter::load(self)
+    }
+}
+
+/// 待機の計測結果（全スレッドの合計）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WaitStats {
+    pub waits: usize,   // 待機した回数
+    pub blocked: usize, // 待機を始めた時点で相手がまだ追いついていなかった回数
+    pub wait: Duration, // 実際に待った時間の合計
+}
+
+impl WaitStats {
+    pub fn merge(self, other: WaitStats) -> WaitStats {
+        WaitStats {
+            waits: self.waits + other.waits,
+            blocked: self.blocked + other.blocked,
+            wait: self.wait + other.wait,
+        }
+    }
+
+    /// カウンタを待ち、待った時間を記録する。すでに追いついていれば時刻を取らない
+    #[inline(always)]
+    pub fn wait_for(&mut self, counter: &impl StepSignal, step: usize) {
+        self.waits += 1;
+        if counter.load() >= step {
+            return;
+        }
+        self.blocked += 1;
+        let start = Instant::now();
+        counter.wait_for(step);
+        self.wait += start.elapsed();
+    }
+}
@@ -0,0 +1,63 @@
+use std::ops::Range;
+use crate::grid::{N, M};
+
+/*
+  空間タイリング（キャッシュブロッキング）の設定
+
+  行全体を走査すると、幅の広い格子（2048列以上）では1行上の値を再利用する前にキャッシュから追い出される。
+  内部領域を height 行 × width 列のタイルに分け、タイル内の3行分（上・現在・下）が
+  L1/L2 に収まる幅で処理することで、読み込んだ行を再利用できるようにする。
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileConfig {
+    pub width: usize,  // タイルの列数
+    pub height: usize, // タイルの行数
+}
+
+impl Default for TileConfig {
+    // 3行 × 512列 × 8バイト = 12KB で L1 に収まる
+    fn default() -> Self {
+        TileConfig { width: 512, height: 32 }
+    }
+}
+
+impl TileConfig {
+    pub fn new(width: usize, height: usize) -> Self {
+        TileConfig {
+            width: width.max(1),
+            height: height.max(1),
+        }
+    }
+
+    /// 内部行 [1, N-1) を height 行ずつに分けた区間
+    pub fn row_blocks(&self) -> Vec<Range<usize>> {
+        blocks(1..N - 1, self.height.max(1))
+    }
+
+    /// 内部列 [1, M-1) を width 列ずつに分けた区間
+    pub fn col_blocks(&self) -> Vec<Range<usize>> {
+        blocks(1..M - 1, self.width.max(1))
+    }
+
+    /// すべてのタイル（行区間, 列区間）。行優先の順に並ぶ
+    pub fn tiles(&self) -> Vec<(Range<usize>, Range<usize>)> {
+        let cols = self.col_blocks();
+        self.row_blocks()
+            .into_iter()
+            .flat_map(|rows| cols.iter().map(move |c| (rows.clone(), c.clone())))
+            .collect()
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+}
+
+fn blocks(range: Range<usize>, size: usize) -> Vec<Range<usize>> {
+    range
+        .clone()
+        .step_by(size)
+        .map(|start| start..(start + size).min(range.end))
+        .collect()
+}
@@ -0,0 +1,209 @@
+use std::f64::consts::PI;
+use crate::grid::{Domain, ALPHA};
+use crate::implementations::safe::runge_kutta::{integrate, Integrator};
+
+/*
+  解析解による検証
+
+  実装どうしの比較（jacobi_step との一致）ではなく、熱方程式の厳密解と比べて
+  離散化そのものの精度を確認する。
+    - Gaussian : 無限領域で広がるガウス分布（境界の影響が無視できる広さの正方形で近似）
+    - SineMode : ディリクレ条件の正方形で減衰する変数分離解 sin(kπx)·sin(lπy)·exp(-απ²(k²+l²)t)
+  領域は一辺 LENGTH の正方形。x 方向 nx 点・y 方向 ny 点の格子で Δx = LENGTH / (nx - 1)、Δy = LENGTH / (ny - 1) とし、
+  外周の格子点は境界値として固定する。nx ≠ ny とすれば長方形セル（Δx ≠ Δy）の離散化を確認できる。
+*/
+
+pub const LENGTH: f64 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnalyticCase {
+    /// 中心 (LENGTH/2, LENGTH/2)、初期分散 sigma0² のガウス分布
+    Gaussian { amplitude: f64, sigma0: f64 },
+    /// x 方向 kx 個、y 方向 ky 個の半波長を持つ正弦モード
+    SineMode { kx: usize, ky: usize },
+}
+
+impl AnalyticCase {
+    /// 連続問題の厳密解
+    pub fn exact(&self, x: f64, y: f64, t: f64) -> f64 {
+        match *self {
+            AnalyticCase::Gaussian { amplitude, sigma0 } => {
+                // 各方向の分散は σ0² + 2αt に広がり、2次元なので振幅はその比で減る
+                let s2 = sigma0 * sigma0 + 2.0 * ALPHA * t;
+                let (cx, cy) = (x - 0.5 * LENGTH, y - 0.5 * LENGTH);
+                amplitude * (sigma0 * sigma0 / s2) * (-(cx * cx + cy * cy) / (2.0 * s2)).exp()
+            }
+            AnalyticCase::SineMode { kx, ky } => {
+                let (ax, ay) = (kx as f64 * PI / LENGTH, ky as f64 * PI / LENGTH);
+                (ax * x).sin() * (ay * y).sin() * (-ALPHA * (ax * ax + ay * ay) * t).exp()
+            }
+        }
+    }
+
+    /// 空間だけ離散化した（時間は連続の）問題の厳密解
+    ///
+    /// 正弦モードは5点ラプラシアンの固有ベクトルなので、減衰率を離散固有値に置き換えれば厳密になる。
+    /// これと比べれば空間誤差を含まない時間積分の誤差だけが取り出せる。Gaussian では None。
+    pub fn semi_discrete(&self, x: f64, y: f64, t: f64, dx: f64, dy: f64) -> Option<f64> {
+        match *self {
+            AnalyticCase::Gaussian { .. } => None,
+            AnalyticCase::SineMode { kx, ky } => {
+                let (ax, ay) = (kx as f64 * PI / LENGTH, ky as f64 * PI / LENGTH);
+                let sx = (0.5 * ax * dx).sin();
+                let sy = (0.5 * ay * dy).sin();
+                let lambda = ALPHA * 4.0 * (sx * sx / (dx * dx) + sy * sy / (dy * dy));
+                Some((ax * x).sin() * (ay * y).sin() * (-lambda * t).exp())
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            AnalyticCase::Gaussian { sigma0, .. } => format!("Gaussian (sigma0={})", sigma0),
+            AnalyticCase::SineMode { kx, ky } => format!("Sine mode ({}, {})", kx, ky),
+        }
+    }
+}
+
+/// 内部格子点での誤差ノルム（L1, L2 は格子点数で正規化）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ErrorNorms {
+    pub l1: f64,
+    pub l2: f64,
+    pub linf: f64,
+}
+
+pub fn error_norms(numerical: &[f64], exact: &[f64], rows: usize, cols: usize) -> ErrorNorms {
+    let mut norms = ErrorNorms::default();
+    let mut count = 0usize;
+    for i in 1..rows - 1 {
+        for j in 1..cols - 1 {
+            let e = (numerical[i * cols + j] - exact[i * cols + j]).abs();
+            norms.l1 += e;
+            norms.l2 += e * e;
+            norms.linf = norms.linf.max(e);
+            count += 1;
+        }
+    }
+    norms.l1 /= count as f64;
+    norms.l2 = (norms.l2 / count as f64).sqrt();
+    norms
+}
+
+/// 比較対象とする解
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reference {
+    /// 連続問題の厳密解（空間 + 時間の誤差）
+    Exact,
+    /// 空間離散化後の厳密解（時間積分の誤差のみ）
+    SemiDiscrete,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ValidationRun {
+    pub n: usize, // x 方向の格子点数（正方格子なら y 方向も同じ）
+    pub dx: f64,
+    pub dy: f64,
+    pub dt: f64,
+    pub steps: usize,
+    pub norms: ErrorNorms,
+}
+
+/// n×n の格子で t_end まで解き、基準解との誤差を求める
+///
+/// dt は t_end をちょうど割り切るように調整される。
+pub fn run_case(
+    case: &AnalyticCase,
+    n: usize,
+    dt: f64,
+    t_end: f64,
+    integrator: Integrator,
+    reference: Reference,
+) -> ValidationRun {
+    run_case_rect(case, n, n, dt, t_end, integrator, reference)
+}
+
+/// nx×ny の格子（Δx ≠ Δy の長方形セル）で t_end まで解き、基準解との誤差を求める
+pub fn run_case_rect(
+    case: &AnalyticCase,
+    nx: usize,
+    ny: usize,
+    dt: f64,
+    t_end: f64,
+    integrator: Integrator,
+    reference: Reference,
+) -> ValidationRun {
+    let dx = LENGTH / (nx - 1) as f64;
+    let dy = LENGTH / (ny - 1) as f64;
+    let steps = ((t_end / dt).round() as usize).max(1);
+    let dt = t_end / steps as f64;
+
+    let domain = Domain {
+        rows: nx,
+        cols: ny,
+        factor_x: ALPHA * dt / (dx * dx),
+        factor_y: ALPHA * dt / (dy * dy),
+        heat_source: None,
+    };
+
+    let mut u: Vec<f64> = (0..nx * ny)
+        .map(|idx| case.exact((idx / ny) as f64 * dx, (idx % ny) as f64 * dy, 0.0))
+        .collect();
+    let mut out = u.clone();
+    integrate(&mut u, &mut out, &domain, integrator, steps);
+
+    let exact: Vec<f64> = (0..nx * ny)
+        .map(|idx| {
+            let (x, y) = ((idx / ny) as f64 * dx, (idx % ny) as f64 * dy);
+            match reference {
+                Reference::Exact => case.exact(x, y, t_end),
+                Reference::SemiDiscrete => case
+                    .semi_discrete(x, y, t_end, dx, dy)
+                    .expect("semi-discrete reference is only available for sine modes"),
+            }
+        })
+        .collect();
+
+    ValidationRun { n: nx, dx, dy, dt, steps, norms: error_norms(&u, &exact, nx, ny) }
+}
+
+/// 空間方向の収束: 拡散数 r = α·Δt/Δx² を固定して格子を細かくする
+///
+/// 時間誤差が空間誤差より十分小さくなる積分法（RK4など）を使えば、観測次数は空間精度（5点ステンシルでは2）になる。
+pub fn spatial_convergence(
+    case: &AnalyticCase,
+    sizes: &[usize],
+    r: f64,
+    t_end: f64,
+    integrator: Integrator,
+) -> Vec<ValidationRun> {
+    sizes
+        .iter()
+        .map(|&n| {
+            let dx = LENGTH / (n - 1) as f64;
+            run_case(case, n, r * dx * dx / ALPHA, t_end, integrator, Reference::Exact)
+        })
+        .collect()
+}
+
+/// 時間方向の収束: 格子を固定して Δt を細かくし、空間離散化後の厳密解と比べる
+pub fn temporal_convergence(
+    case: &AnalyticCase,
+    n: usize,
+    dts: &[f64],
+    t_end: f64,
+    integrator: Integrator,
+) -> Vec<ValidationRun> {
+    dts.iter()
+        .map(|&dt| run_case(case, n, dt, t_end, integrator, Reference::SemiDiscrete))
+        .collect()
+}
+
+/// 連続する2つの結果から観測収束次数 log(e1/e2) / log(h1/h2) を求める
+///
+/// h には空間収束なら Δx、時間収束なら Δt を渡す。
+pub fn observed_orders(runs: &[ValidationRun], h: impl Fn(&ValidationRun) -> f64) -> Vec<f64> {
+    runs.windows(2)
+        .map(|w| (w[0].norms.l2 / w[1].norms.l2).ln() / (h(&w[0]) / h(&w[1])).ln())
+        .collect()
+}
@@ -0,0 +1,49 @@
+use jacobi_rust::affinity::{self, PinPolicy};
+
+#[test]
+fn test_pin_policy_parse() {
+    assert_eq!(PinPolicy::parse("none"), Ok(PinPolicy::None));
+    assert_eq!(PinPolicy::parse("Scatter"), Ok(PinPolicy::Scatter));
+    assert_eq!(PinPolicy::parse("no-smt"), Ok(PinPolicy::NoSmt));
+    assert_eq!(PinPolicy::parse("0, 2,4"), Ok(PinPolicy::Cores(vec![0, 2, 4])));
+    assert!(PinPolicy::parse("everywhere").is_err());
+
+    for policy in [PinPolicy::Compact, PinPolicy::Scatter, PinPolicy::NoSmt, PinPolicy::Cores(vec![1, 3])] {
+        assert_eq!(PinPolicy::parse(&policy.label().replace("cores=", "")), Ok(policy));
+    }
+}
+
+#[test]
+fn test_cpu_assignment() {
+    assert_eq!(PinPolicy::None.cpu_for(0), None);
+    assert_eq!(PinPolicy::Cores(vec![5, 7]).cpu_for(3), Some(7));
+
+    // どの方針も使える CPU を重複なく並べ、ワーカー番号は並び順を一周する
+    for policy in [PinPolicy::Compact, PinPolicy::Scatter, PinPolicy::NoSmt] {
+        let order = policy.cpu_order();
+        assert!(!order.is_empty(), "{}", policy.label());
+        let mut unique = order.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), order.len(), "{}", policy.label());
+        assert_eq!(policy.cpu_for(order.len()), Some(order[0]));
+    }
+
+    // 固定した CPU で動いていること（スレッドを分けてテスト全体の CPU マスクを変えない）
+    let cpu = PinPolicy::Compact.cpu_for(0).unwrap();
+    std::thread::spawn(move || {
+        affinity::pin_current(cpu).unwrap();
+        assert_eq!(affinity::current_cpu(), Some(cpu));
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn test_set_policy_rejects_unavailable_cores() {
+    // 使えない CPU を含む Cores は拒否され、方針は変わらない
+    let before = affinity::policy();
+    assert!(affinity::set_policy(PinPolicy::Cores(vec![usize::MAX])).is_err());
+    assert!(affinity::set_policy(PinPolicy::Cores(Vec::new())).is_err());
+    assert_eq!(affinity::policy(), before);
+}
@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+use jacobi_rust::barriers::{BandBarrier, CondvarBarrier, FutexBarrier, ParkBarrier};
+use jacobi_rust::grid::Grid;
+use jacobi_rust::implementations::safe::single::jacobi_step;
+use jacobi_rust::implementations::unsafe_impl::blocking::band_barrier;
+
+type BandSolver = fn(&mut Grid, &mut Grid, usize, usize);
+
+#[test]
+fn test_band_barriers_match_single() {
+    let steps = 15;
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    jacobi_step(&mut ref_a, &mut ref_b, steps);
+
+    let solvers: [(&str, BandSolver); 4] = [
+        (Barrier::NAME, band_barrier::<Barrier>),
+        (CondvarBarrier::NAME, band_barrier::<CondvarBarrier>),
+        (FutexBarrier::NAME, band_barrier::<FutexBarrier>),
+        (ParkBarrier::NAME, band_barrier::<ParkBarrier>),
+    ];
+    for (name, solver) in solvers {
+        for threads in [1, 2, 3] {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            solver(&mut a, &mut b, steps, threads);
+            assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "{name} threads={threads}");
+        }
+    }
+}
+
+// 各ラウンドで全員がカウンタを増やし、バリアの後に全員分が揃っていることを確かめる
+fn check_rounds<B: BandBarrier>() {
+    let threads = 4;
+    let rounds = 200;
+    let barrier = B::new(threads);
+    let counter = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for id in 0..threads {
+            let (barrier, counter) = (&barrier, &counter);
+            scope.spawn(move || {
+                barrier.register(id);
+                for round in 1..=rounds {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    barrier.wait(id);
+                    assert_eq!(counter.load(Ordering::Relaxed), round * threads, "{}", B::NAME);
+                    barrier.wait(id);
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn test_barrier_generations() {
+    check_rounds::<Barrier>();
+    check_rounds::<CondvarBarrier>();
+    check_rounds::<FutexBarrier>();
+    check_rounds::<ParkBarrier>();
+}
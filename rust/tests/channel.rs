@@ -0,0 +1,50 @@
+use jacobi_rust::grid::Grid;
+use jacobi_rust::implementations::safe::channel::{channel_mpsc, channel_spsc};
+use jacobi_rust::implementations::safe::single::jacobi_step;
+use jacobi_rust::spsc::{row_ring, Disconnected};
+
+#[test]
+fn test_channel_exchange_matches_single() {
+    let steps = 15;
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    jacobi_step(&mut ref_a, &mut ref_b, steps);
+
+    for threads in [1, 2, 3, 5] {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        channel_mpsc(&mut a, &mut b, steps, threads);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "mpsc threads={threads}");
+
+        for capacity in [1, 4] {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            channel_spsc(&mut a, &mut b, steps, threads, capacity);
+            assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "spsc threads={threads} capacity={capacity}");
+        }
+    }
+}
+
+#[test]
+fn test_row_ring_order_and_disconnect() {
+    let (mut tx, mut rx) = row_ring(2, 3);
+    let rounds = 1000;
+
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            for k in 0..rounds {
+                let v = k as f64;
+                tx.push(&[v, v + 0.5, -v]).unwrap();
+            }
+            // ここで tx が drop される
+        });
+        let mut row = [0.0; 3];
+        for k in 0..rounds {
+            rx.pop_into(&mut row).unwrap();
+            let v = k as f64;
+            assert_eq!(row, [v, v + 0.5, -v]);
+        }
+        // 送り手が終わった後は、待ち続けずに Err になる
+        assert_eq!(rx.pop_into(&mut row), Err(Disconnected));
+    });
+}
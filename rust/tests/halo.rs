@@ -0,0 +1,16 @@
+use jacobi_rust::halo::AtomicRow;
+
+#[test]
+fn test_atomic_row_round_trip() {
+    let row = AtomicRow::new(5);
+    assert_eq!(row.len(), 5);
+    assert_eq!(row.get(3), 0.0);
+
+    // ビット列のまま保存するので、-0.0 や非正規化数もそのまま戻る
+    let values = [1.5, -0.0, f64::MIN_POSITIVE / 2.0, 100.0, -3.25];
+    row.store(&values);
+    let mut out = [0.0; 5];
+    row.load_into(&mut out);
+    assert_eq!(out.map(f64::to_bits), values.map(f64::to_bits));
+    assert_eq!(row.get(0), 1.5);
+}
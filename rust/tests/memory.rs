@@ -0,0 +1,34 @@
+use jacobi_rust::grid::{Grid, Placement};
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter;
+use jacobi_rust::memory::{tlb_report, PageMode};
+
+#[test]
+fn test_page_modes_align_and_match() {
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    unsafe_atomic_counter(&mut ref_a, &mut ref_b, 20);
+
+    for pages in PageMode::ALL {
+        assert_eq!(PageMode::parse(pages.label()), Ok(pages));
+        for placement in [Placement::MainThread, Placement::FirstTouch { threads: 2 }] {
+            let mut a = Grid::with_options(placement, pages);
+            let mut b = Grid::with_options(placement, pages);
+            // hugetlbfs のページが予約されていない環境では THP で確保される
+            let align = a.data.mode().alignment();
+            assert_eq!(a.data.as_ptr() as usize % align, 0, "{}", pages.label());
+            assert_eq!(a.data.as_slice(), Grid::new().data.as_slice(), "{}", pages.label());
+
+            unsafe_atomic_counter(&mut a, &mut b, 20);
+            assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "{}", pages.label());
+            assert_eq!(a.clone().data.as_slice(), a.data.as_slice());
+        }
+    }
+    assert!(PageMode::parse("1g").is_err());
+}
+
+#[test]
+fn test_tlb_report() {
+    let grid = Grid::with_options(Placement::MainThread, PageMode::HugePage);
+    let report = tlb_report(&grid.data);
+    assert!(report.contains("THP=") && report.contains("KernelPageSize="), "{report}");
+}
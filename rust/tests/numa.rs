@@ -0,0 +1,33 @@
+use jacobi_rust::grid::{Grid, Placement, N, M};
+use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::barrier_unsafe;
+use jacobi_rust::numa::{page_placement, parse_numa_maps_line, PagePlacement};
+
+#[test]
+fn test_first_touch_grid_matches_default() {
+    let expected = Grid::new();
+    for threads in [1, 2, 3, 8] {
+        let mut a = Grid::with_placement(Placement::FirstTouch { threads });
+        let mut b = Grid::with_placement(Placement::FirstTouch { threads });
+        assert_eq!(a.data.as_slice(), expected.data.as_slice(), "threads={threads}");
+        assert_eq!(a.data.as_ptr() as usize % 64, 0);
+
+        let mut ref_a = Grid::new();
+        let mut ref_b = Grid::new();
+        barrier_unsafe(&mut a, &mut b, 10);
+        barrier_unsafe(&mut ref_a, &mut ref_b, 10);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "threads={threads}");
+    }
+}
+
+#[test]
+fn test_page_placement() {
+    let line = "7f3a2c000000 default anon=256 dirty=256 active=0 N0=100 N1=156 kernelpagesize_kB=4";
+    assert_eq!(parse_numa_maps_line(line), PagePlacement { nodes: vec![(0, 100), (1, 156)], page_kb: 4 });
+    assert_eq!(parse_numa_maps_line(line).label(), "N0=100 N1=156 (4kB pages)");
+
+    // numa_maps が読める環境では、格子全体のページがどこかのノードに数えられている
+    let grid = Grid::with_placement(Placement::FirstTouch { threads: 2 });
+    if let Ok(pages) = page_placement(&grid.data) {
+        assert!(pages.total_pages() * pages.page_kb * 1024 >= N * M * 8, "{}", pages.label());
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use jacobi_rust::grid::Grid;
+use jacobi_rust::implementations::safe::single::jacobi_step;
+use jacobi_rust::implementations::safe::pooled::PooledSolver;
+use jacobi_rust::implementations::unsafe_impl::pooled::UnsafePooledSolver;
+use jacobi_rust::pool::BandPool;
+
+const TEST_STEPS: usize = 11;
+
+#[test]
+fn test_pooled_solver_reuse() {
+    // 同じソルバー（同じスレッド）で繰り返し計算しても、毎回 jacobi_step と一致する
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step(&mut single_a, &mut single_b, TEST_STEPS);
+
+    for num_threads in [1, 3, 4] {
+        let mut solver = UnsafePooledSolver::new(num_threads);
+        let mut safe_solver = PooledSolver::new(num_threads);
+        for _ in 0..3 {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            solver.barrier(&mut a, &mut b, TEST_STEPS);
+            assert!(single_a.data.iter().zip(a.data.iter()).all(|(x, y)| x == y), "Pooled Barrier Unsafe ({} threads)", num_threads);
+
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            solver.atomic_counter(&mut a, &mut b, TEST_STEPS);
+            assert!(single_a.data.iter().zip(a.data.iter()).all(|(x, y)| x == y), "Pooled Atomic Counter Unsafe ({} threads)", num_threads);
+
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            safe_solver.barrier(&mut a, &mut b, TEST_STEPS);
+            assert!(single_a.data.iter().zip(a.data.iter()).all(|(x, y)| x == y), "Pooled Barrier ({} threads)", num_threads);
+
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            safe_solver.atomic_counter(&mut a, &mut b, TEST_STEPS);
+            assert!(single_a.data.iter().zip(a.data.iter()).all(|(x, y)| x == y), "Pooled Atomic Counter ({} threads)", num_threads);
+        }
+    }
+
+    println!("✓ Pooled solvers match single thread across runs!");
+}
+
+#[test]
+fn test_band_pool_runs_each_worker_once() {
+    let mut pool = BandPool::new(4);
+    let calls: Vec<AtomicUsize> = (0..4).map(|_| AtomicUsize::new(0)).collect();
+
+    for _ in 0..5 {
+        pool.run(&|id| {
+            calls[id].fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    assert!(calls.iter().all(|c| c.load(Ordering::Relaxed) == 5));
+
+    // ワーカーの panic は run の呼び出し元に伝わり、プールはその後も使える
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.run(&|id| assert_ne!(id, 2))));
+    assert!(result.is_err());
+    pool.run(&|id| {
+        calls[id].fetch_add(1, Ordering::Relaxed);
+    });
+    assert!(calls.iter().all(|c| c.load(Ordering::Relaxed) == 6));
+
+    println!("✓ Band pool runs every worker once per job!");
+}
@@ -0,0 +1,47 @@
+use jacobi_rust::double_buffer::BandedDoubleBuffer;
+use jacobi_rust::grid::{Grid, N, M};
+use jacobi_rust::implementations::safe::scoped_bands::scoped_bands;
+use jacobi_rust::implementations::safe::single::jacobi_step;
+
+#[test]
+fn test_scoped_bands_match_single() {
+    for steps in [0, 1, 14] {
+        let mut ref_a = Grid::new();
+        let mut ref_b = Grid::new();
+        jacobi_step(&mut ref_a, &mut ref_b, steps);
+
+        for threads in [1, 2, 3, 7] {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            scoped_bands(&mut a, &mut b, steps, threads);
+            assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "steps={steps} threads={threads}");
+        }
+    }
+}
+
+#[test]
+fn test_step_view_neighbors() {
+    let mut grid = Grid::new();
+    for (idx, v) in grid.data.iter_mut().enumerate() {
+        *v = (idx / M) as f64;
+    }
+    let buffers = BandedDoubleBuffer::from_grid(&grid, 3);
+
+    // 中央の帯は、上の帯の最終行と下の帯の先頭行を読める
+    let mut view = buffers.step_view(0, 1);
+    let rows = view.rows.clone();
+    let (above, src, below, dst) = view.parts();
+    assert_eq!(above.unwrap()[0], (rows.start - 1) as f64);
+    assert_eq!(below.unwrap()[0], rows.end as f64);
+    assert_eq!(src.len(), rows.len() * M);
+    assert_eq!(dst.len(), rows.len() * M);
+    drop(view);
+
+    // 端の帯には外側の隣が無い
+    let mut first = buffers.step_view(1, 0);
+    assert!(first.parts().0.is_none());
+    drop(first);
+    let mut last = buffers.step_view(1, 2);
+    assert!(last.parts().2.is_none());
+    assert_eq!(last.rows.end, N);
+}
@@ -0,0 +1,124 @@
+use std::thread;
+use jacobi_rust::grid::{band_ranges, Grid, N, M};
+use jacobi_rust::implementations::safe::single::jacobi_step;
+use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::barrier_unsafe;
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter_with;
+use jacobi_rust::shared_grid::{BandSync, SharedGrid};
+use jacobi_rust::stencil::{FourthOrder, Stencil};
+use jacobi_rust::implementations::safe::single::jacobi_step_stencil;
+use jacobi_rust::sync::WaitStrategy;
+
+// 各帯が自分の行に「読んだ行の値の和」を書くだけの N 帯の実行。ステップの偶奇と読める範囲を確かめる
+fn run_bands(a: &mut Grid, b: &mut Grid, parts: usize, steps: usize, sync: BandSync) {
+    let shared = SharedGrid::new(a, b, band_ranges(N, parts), sync);
+    thread::scope(|scope| {
+        for mut token in shared.tokens() {
+            scope.spawn(move || {
+                for step in 0..steps {
+                    assert_eq!(token.steps_done(), step);
+                    token.step(|mut view| {
+                        let rows = view.rows();
+                        for i in rows.start.max(1)..rows.end.min(N - 1) {
+                            let (up, curr, down, out) = view.stencil_rows(i);
+                            out[0] = up[0] + curr[0] + down[0];
+                        }
+                    });
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn test_shared_grid_band_views() {
+    for sync in [BandSync::Barrier, BandSync::Neighbors(WaitStrategy::SpinYield { spins: 16 })] {
+        for parts in [1, 2, 5] {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            for i in 0..N {
+                a.data[i * M] = i as f64;
+                b.data[i * M] = -1.0;
+            }
+
+            // 1ステップ目は a を読んで b に書く。帯の境界でも上下の行が読めている
+            run_bands(&mut a, &mut b, parts, 1, sync);
+            for i in 1..N - 1 {
+                assert_eq!(b.data[i * M], (3 * i) as f64, "sync={sync:?} parts={parts} row={i}");
+            }
+            assert_eq!(b.data[0], -1.0);
+            assert_eq!(a.data[5 * M], 5.0);
+        }
+    }
+}
+
+#[test]
+fn test_shared_grid_solvers_match_single() {
+    for steps in [1, 6, 13] {
+        let mut ref_a = Grid::new();
+        let mut ref_b = Grid::new();
+        jacobi_step(&mut ref_a, &mut ref_b, steps);
+
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        barrier_unsafe(&mut a, &mut b, steps);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "barrier_unsafe steps={steps}");
+
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        unsafe_atomic_counter_with(&mut a, &mut b, steps, WaitStrategy::Backoff { max_spins: 64 });
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "unsafe_atomic_counter steps={steps}");
+    }
+}
+
+#[test]
+fn test_shared_grid_overlapped_steps_match_single() {
+    // 内部行を待つ前に計算しても、帯の数やハローの幅によらずビット単位で一致する
+    let stencil = FourthOrder;
+    let steps = 9;
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    jacobi_step_stencil(&mut ref_a, &mut ref_b, &stencil, steps);
+
+    for parts in [1, 2, 5] {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        {
+            let shared = SharedGrid::new(&mut a, &mut b, band_ranges(N, parts), BandSync::Neighbors(WaitStrategy::default()))
+                .with_halo(FourthOrder::RADIUS)
+                .timed();
+            thread::scope(|scope| {
+                for mut token in shared.tokens() {
+                    let stencil = &stencil;
+                    scope.spawn(move || {
+                        for _ in 0..steps {
+                            token.step_overlapped(|mut view| view.apply(stencil));
+                        }
+                        // 待つのは上下の帯のカウンタだけ（ステップごとに1回ずつ）
+                        let neighbors = usize::from(token.id() > 0) + usize::from(token.id() + 1 < parts);
+                        assert_eq!(token.wait_stats().waits, steps * neighbors);
+                    });
+                }
+            });
+        }
+        a.data.copy_from_slice(&b.data);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "parts={parts}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "already issued")]
+fn test_shared_grid_tokens_once() {
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    let shared = SharedGrid::new(&mut a, &mut b, band_ranges(N, 2), BandSync::Barrier);
+    let _first = shared.tokens();
+    let _second = shared.tokens();
+}
+
+#[test]
+#[should_panic(expected = "tile 0..N")]
+fn test_shared_grid_rejects_overlapping_bands() {
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    SharedGrid::new(&mut a, &mut b, vec![0..N / 2 + 1, N / 2..N], BandSync::Barrier);
+}
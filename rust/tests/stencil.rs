@@ -0,0 +1,152 @@
+use jacobi_rust::grid::{Grid, ALPHA, DT, DX, DY, N, M};
+use jacobi_rust::diffusivity::Diffusivity;
+use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter_stencil;
+use jacobi_rust::implementations::safe::barrier::barrier_parallel::{barrier_parallel_bands, barrier_parallel_stencil};
+use jacobi_rust::implementations::safe::rayon::rayon::rayon_parallel_stencil;
+use jacobi_rust::implementations::safe::single::{jacobi_step, jacobi_step_stencil};
+use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::{barrier_unsafe_bands, barrier_unsafe_stencil};
+use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::rayon_unsafe_stencil;
+use jacobi_rust::implementations::unsafe_impl::single_unsafe::jacobi_step_unsafe_stencil;
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter_stencil;
+use jacobi_rust::sync::WaitStrategy;
+use jacobi_rust::stencil::{FivePoint, FourthOrder, NinePoint, Stencil};
+
+const TEST_STEPS: usize = 10;
+const EPSILON: f64 = 1e-10;
+
+fn grids_are_equal(grid1: &Grid, grid2: &Grid) -> bool {
+    for i in 0..grid1.data.len() {
+        let diff = (grid1.data[i] - grid2.data[i]).abs();
+        if diff > EPSILON {
+            eprintln!("Mismatch at index {}: {} vs {} (diff: {})", i, grid1.data[i], grid2.data[i], diff);
+            return false;
+        }
+    }
+    true
+}
+
+type StencilImpl<S> = fn(&mut Grid, &mut Grid, &S, usize);
+
+// ステンシルを引数に取る各実装が single と一致することを確認する
+fn check_parallel_matches<S: Stencil>(stencil: &S) {
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step_stencil(&mut single_a, &mut single_b, stencil, TEST_STEPS);
+
+    let impls: [(&str, StencilImpl<S>); 4] = [
+        ("rayon", rayon_parallel_stencil),
+        ("rayon unsafe", rayon_unsafe_stencil),
+        ("single unsafe", jacobi_step_unsafe_stencil),
+        ("barrier", barrier_parallel_stencil),
+    ];
+    for (name, f) in impls {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        f(&mut a, &mut b, stencil, TEST_STEPS);
+        assert!(grids_are_equal(&single_a, &a), "{} {} does not match single", S::NAME, name);
+    }
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    atomic_counter_stencil(&mut a, &mut b, stencil, TEST_STEPS, WaitStrategy::default());
+    assert!(grids_are_equal(&single_a, &a), "{} atomic counter does not match single", S::NAME);
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    barrier_unsafe_stencil(&mut a, &mut b, stencil, TEST_STEPS);
+    assert!(grids_are_equal(&single_a, &a), "{} barrier unsafe does not match single", S::NAME);
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    unsafe_atomic_counter_stencil(&mut a, &mut b, stencil, TEST_STEPS, WaitStrategy::default());
+    assert!(grids_are_equal(&single_a, &a), "{} unsafe atomic counter does not match single", S::NAME);
+
+    for threads in [1, 3, 5] {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        barrier_parallel_bands(&mut a, &mut b, stencil, TEST_STEPS, threads);
+        assert!(grids_are_equal(&single_a, &a), "{} barrier ({} threads) does not match single", S::NAME, threads);
+
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        barrier_unsafe_bands(&mut a, &mut b, stencil, TEST_STEPS, threads);
+        assert!(grids_are_equal(&single_a, &a), "{} barrier unsafe ({} threads) does not match single", S::NAME, threads);
+    }
+
+    assert_eq!(single_a.data[(N / 2) * M + M / 2], 100.0, "Heat source should remain 100.0");
+    assert!(DT <= stencil.dt_limit());
+}
+
+#[test]
+fn test_five_point_matches_jacobi_step() {
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step(&mut single_a, &mut single_b, TEST_STEPS);
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    jacobi_step_stencil(&mut a, &mut b, &FivePoint, TEST_STEPS);
+
+    assert!(grids_are_equal(&single_a, &a), "Generic 5-point does not match jacobi_step");
+
+    println!("✓ Generic 5-point vs jacobi_step: Results match!");
+}
+
+#[test]
+fn test_stencils_parallel_match() {
+    check_parallel_matches(&FivePoint);
+    check_parallel_matches(&NinePoint);
+    check_parallel_matches(&FourthOrder);
+    check_parallel_matches(&Diffusivity::copper_trace());
+
+    println!("✓ 5-point / 9-point / 4th order / variable α: every strategy matches single!");
+}
+
+#[test]
+fn test_fourth_order_halo_width() {
+    // 2行のゴースト行が正しく交換されていれば、2行ずつの帯でも中心から2行先まで値が届く
+    // （±2セルの係数は負なので、1ステップ後の値も負になる）
+    type BandsImpl = fn(&mut Grid, &mut Grid, &FourthOrder, usize, usize);
+    let impls: [(&str, BandsImpl); 2] = [("safe", barrier_parallel_bands), ("unsafe", barrier_unsafe_bands)];
+    for (name, f) in impls {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        f(&mut a, &mut b, &FourthOrder, 1, N / 2);
+
+        let center = (N / 2) * M + M / 2;
+        assert!(a.data[center - 2 * M] < 0.0 && a.data[center + 2 * M] < 0.0, "{name}: halo not exchanged");
+        assert_eq!(a.data[center - 3 * M], 0.0);
+
+        // 外周2セル分は固定値
+        for i in 0..N {
+            for j in 0..M {
+                if FourthOrder::is_fixed(i, j) && !(i == N / 2 && j == M / 2) {
+                    assert_eq!(a.data[i * M + j], 0.0);
+                }
+            }
+        }
+    }
+
+    println!("✓ 4th order halo exchange with 2-row bands!");
+}
+
+#[test]
+fn test_nine_point_isotropic_form() {
+    // Δx = Δy = h のとき、異方性を入れた重みは従来の [4·(上下左右) + (斜め4点) - 20·u] / (6h²) と一致する
+    assert_eq!(DX, DY, "this check assumes square cells");
+    let src: Vec<f64> = (0..3 * M).map(|k| ((k * 37) % 101) as f64 / 7.0).collect();
+    let mut dst = vec![0.0; M];
+    NinePoint.apply_row(1, |k| &src[k * M..(k + 1) * M], &mut dst);
+
+    let factor = ALPHA * DT / (DX * DX);
+    let (up, cur, down) = (&src[..M], &src[M..2 * M], &src[2 * M..]);
+    for j in 1..M - 1 {
+        let edges = up[j] + down[j] + cur[j - 1] + cur[j + 1];
+        let corners = up[j - 1] + up[j + 1] + down[j - 1] + down[j + 1];
+        let expected = cur[j] + factor * (4.0 * edges + corners - 20.0 * cur[j]) / 6.0;
+        assert!((dst[j] - expected).abs() < 1e-12, "column {j}: {} vs {}", dst[j], expected);
+    }
+    assert!((NinePoint.dt_limit() - 3.0 * DX * DX / (8.0 * ALPHA)).abs() < 1e-15);
+
+    println!("✓ 9-point with Δx = Δy matches the isotropic stencil!");
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use jacobi_rust::grid::Grid;
+use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter_with;
+use jacobi_rust::implementations::safe::single::jacobi_step;
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter_with;
+use jacobi_rust::sync::{AlignedSpinCounter, SpinCounter, StepCounter, StepSignal, WaitStrategy};
+
+#[test]
+fn test_wait_strategies_match_single() {
+    let steps = 25;
+    let mut ref_a = Grid::new();
+    let mut ref_b = Grid::new();
+    jacobi_step(&mut ref_a, &mut ref_b, steps);
+
+    for strategy in WaitStrategy::PRESETS {
+        assert_eq!(WaitStrategy::parse(&strategy.label()), Ok(strategy));
+
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        atomic_counter_with(&mut a, &mut b, steps, strategy);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "safe {}", strategy.label());
+
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        unsafe_atomic_counter_with(&mut a, &mut b, steps, strategy);
+        assert_eq!(a.data.as_slice(), ref_a.data.as_slice(), "unsafe {}", strategy.label());
+    }
+    assert_eq!(WaitStrategy::parse("park"), Ok(WaitStrategy::SpinPark { spins: 256 }));
+    assert!(WaitStrategy::parse("spin:10").is_err());
+}
+
+#[test]
+fn test_step_counter_ping_pong() {
+    // 2つのカウンタで交互にステップを進める。眠った側が publish で起こされないと終わらない
+    for strategy in WaitStrategy::PRESETS {
+        let ping = Arc::new(StepCounter::new(strategy));
+        let pong = Arc::new(StepCounter::new(strategy));
+        let rounds = 200;
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for step in 1..=rounds {
+                    ping.publish(step);
+                    pong.wait_for(step);
+                }
+            });
+            for step in 1..=rounds {
+                ping.wait_for(step);
+                pong.publish(step);
+            }
+        });
+        assert_eq!((ping.load(), pong.load()), (rounds, rounds), "{}", strategy.label());
+    }
+}
+
+#[test]
+fn test_baseline_counter_layouts() {
+    // 既定の atomic_counter のカウンタはパディングの無い AtomicUsize、unsafe 版は64バイト境界に置く
+    assert_eq!(std::mem::size_of::<SpinCounter>(), std::mem::size_of::<usize>());
+    assert_eq!(std::mem::align_of::<AlignedSpinCounter>(), 64);
+
+    let counters = Arc::new([SpinCounter::new(), SpinCounter::new()]);
+    let peer = counters.clone();
+    let handle = std::thread::spawn(move || {
+        for step in 1..=100 {
+            peer[1].publish(step);
+            peer[0].wait_for(step);
+        }
+    });
+    for step in 1..=100 {
+        counters[0].publish(step);
+        counters[1].wait_for(step);
+    }
+    handle.join().unwrap();
+    assert_eq!(counters[1].load(), 100);
+}
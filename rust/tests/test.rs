@@ -8,10 +8,32 @@ use jacobi_rust::implementations::safe::rayon::rayon::rayon_parallel;
 use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter;
 use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::rayon_unsafe;
 use jacobi_rust::implementations::unsafe_impl::single_unsafe::jacobi_step_unsafe;
+use jacobi_rust::implementations::safe::crank_nicolson::{crank_nicolson, CrankNicolsonConfig, InnerSolver};
+use jacobi_rust::grid::{explicit_dt_limit, is_dirichlet, DT, DX, DY};
+use jacobi_rust::implementations::safe::adi::{adi_single, adi_rayon, AdiConfig, ColumnSweep};
+use jacobi_rust::implementations::unsafe_impl::adi_unsafe::adi_band_unsafe;
+use jacobi_rust::implementations::safe::runge_kutta::{runge_kutta, Integrator, RungeKuttaConfig};
+use jacobi_rust::diffusivity::{Diffusivity, FR4_ALPHA};
+use jacobi_rust::grid::ALPHA;
+use jacobi_rust::implementations::safe::single::jacobi_step_var;
+use jacobi_rust::implementations::unsafe_impl::single_unsafe::jacobi_step_unsafe_var;
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter_var;
+use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter_var;
+use jacobi_rust::implementations::safe::barrier::barrier_parallel::barrier_parallel_var;
+use jacobi_rust::implementations::unsafe_impl::barrier_unsafe::barrier_unsafe_var;
+use jacobi_rust::implementations::safe::rayon::rayon::rayon_parallel_var;
+use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::rayon_unsafe_var;
+use jacobi_rust::implementations::safe::atomic_counter::atomic_counter::atomic_counter_overlap;
+use jacobi_rust::halo::HaloSchedule;
+use jacobi_rust::sync::WaitStats;
+use jacobi_rust::implementations::unsafe_impl::unsafe_atomic_counter::unsafe_atomic_counter_overlap;
 
 const TEST_STEPS: usize = 10;
 const EPSILON: f64 = 1e-10;
 
+type VarImpl = fn(&mut Grid, &mut Grid, &Diffusivity, usize);
+type OverlapImpl = fn(&mut Grid, &mut Grid, usize, HaloSchedule) -> WaitStats;
+
 /// グリッドの全要素が一致するかチェック
 fn grids_are_equal(grid1: &Grid, grid2: &Grid) -> bool {
     if grid1.data.len() != grid2.data.len() {
@@ -34,7 +56,7 @@ fn grids_are_equal(grid1: &Grid, grid2: &Grid) -> bool {
 
 /// 最終的な結果がどちらのグリッドにあるか判定して返すヘルパー
 fn get_final_grid<'a>(grid_a: &'a Grid, grid_b: &'a Grid) -> &'a Grid {
-    if TEST_STEPS % 2 == 0 {
+    if TEST_STEPS.is_multiple_of(2) {
         grid_a
     } else {
         grid_b
@@ -275,4 +297,360 @@ fn test_single_safe_vs_unsafe() {
     );
 
     println!("✓ Single Safe vs Unsafe: Results match!");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_crank_nicolson_jacobi_vs_cg() {
+    // 内部反復法が違っても、十分に収束させれば同じ解になる
+    let jacobi_config = CrankNicolsonConfig {
+        solver: InnerSolver::Jacobi,
+        tolerance: 1e-12,
+        ..Default::default()
+    };
+    let cg_config = CrankNicolsonConfig {
+        solver: InnerSolver::ConjugateGradient,
+        tolerance: 1e-12,
+        ..Default::default()
+    };
+
+    let mut jacobi_a = Grid::new();
+    let mut jacobi_b = Grid::new();
+    let jacobi_stats = crank_nicolson(&mut jacobi_a, &mut jacobi_b, TEST_STEPS, &jacobi_config);
+
+    let mut cg_a = Grid::new();
+    let mut cg_b = Grid::new();
+    let cg_stats = crank_nicolson(&mut cg_a, &mut cg_b, TEST_STEPS, &cg_config);
+
+    assert!(jacobi_stats.max_residual <= 1e-12 && cg_stats.max_residual <= 1e-12);
+    assert!(
+        grids_are_equal(&jacobi_a, &cg_a),
+        "Crank-Nicolson with Jacobi and CG inner solvers produce different results"
+    );
+
+    println!("✓ Crank-Nicolson Jacobi vs CG: Results match!");
+}
+
+#[test]
+fn test_crank_nicolson_large_dt_stable() {
+    // 陽解法の安定限界を大きく超える時間刻みでも発散しない
+    let config = CrankNicolsonConfig {
+        dt: explicit_dt_limit() * 20.0,
+        ..Default::default()
+    };
+
+    let mut grid_a = Grid::new();
+    let mut grid_b = Grid::new();
+    let stats = crank_nicolson(&mut grid_a, &mut grid_b, TEST_STEPS, &config);
+
+    assert!(stats.max_residual <= config.tolerance);
+    assert_eq!(grid_a.data[N / 2 * M + M / 2], 100.0, "Heat source should remain 100.0");
+    for &v in grid_a.data.iter() {
+        assert!(v.is_finite() && (0.0..=100.0 + 1e-9).contains(&v), "Unstable value: {}", v);
+    }
+    for j in 0..M {
+        assert_eq!(grid_a.data[j], 0.0);
+        assert_eq!(grid_a.data[(N - 1) * M + j], 0.0);
+    }
+
+    println!("✓ Crank-Nicolson stable with large dt!");
+}
+
+/// 固定セル以外で diag·x - wx·(x[i±1]) - wy·(x[j±1]) = rhs を Gauss–Seidel で解く（検証用の参照解）
+///
+/// diag = 1 + 2·(wx + wy)。固定セル（is_dirichlet）は x の値をそのまま残す。
+fn reference_implicit_solve(x: &mut [f64], rhs: &[f64], wx: f64, wy: f64) {
+    let diag = 1.0 + 2.0 * (wx + wy);
+    for _sweep in 0..10_000 {
+        let mut max_update: f64 = 0.0;
+        for i in 1..N - 1 {
+            for j in 1..M - 1 {
+                if is_dirichlet(i, j) {
+                    continue;
+                }
+                let idx = i * M + j;
+                let new = (rhs[idx] + wx * (x[idx - M] + x[idx + M]) + wy * (x[idx - 1] + x[idx + 1])) / diag;
+                max_update = max_update.max((new - x[idx]).abs());
+                x[idx] = new;
+            }
+        }
+        if max_update < 1e-14 {
+            return;
+        }
+    }
+    panic!("reference solve did not converge");
+}
+
+/// (I + wx·δi² + wy·δj²) u を固定セル以外に作る（固定セルは u の値）
+fn reference_explicit_part(u: &[f64], wx: f64, wy: f64) -> Vec<f64> {
+    let mut out = u.to_vec();
+    for i in 1..N - 1 {
+        for j in 1..M - 1 {
+            if is_dirichlet(i, j) {
+                continue;
+            }
+            let idx = i * M + j;
+            out[idx] = u[idx]
+                + wx * (u[idx - M] - 2.0 * u[idx] + u[idx + M])
+                + wy * (u[idx - 1] - 2.0 * u[idx] + u[idx + 1]);
+        }
+    }
+    out
+}
+
+#[test]
+fn test_crank_nicolson_matches_reference() {
+    // 連立方程式 (I - r/2·L) u^{n+1} = (I + r/2·L) u^n を素朴な Gauss–Seidel で解いた参照解と比較する
+    for dt in [DT, explicit_dt_limit() * 20.0] {
+        let (hx, hy) = (0.5 * ALPHA * dt / (DX * DX), 0.5 * ALPHA * dt / (DY * DY));
+        let mut reference = Grid::new().data.to_vec();
+        for _ in 0..TEST_STEPS {
+            let rhs = reference_explicit_part(&reference, hx, hy);
+            reference_implicit_solve(&mut reference, &rhs, hx, hy);
+        }
+
+        for solver in [InnerSolver::Jacobi, InnerSolver::ConjugateGradient] {
+            let config = CrankNicolsonConfig { dt, solver, tolerance: 1e-12, max_iterations: 10_000 };
+            let mut cn_a = Grid::new();
+            let mut cn_b = Grid::new();
+            crank_nicolson(&mut cn_a, &mut cn_b, TEST_STEPS, &config);
+
+            let max_diff = reference.iter()
+                .zip(cn_a.data.iter())
+                .fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+            assert!(max_diff < 1e-9, "Crank-Nicolson ({solver:?}, dt={dt}) deviates from reference: {max_diff}");
+        }
+    }
+
+    println!("✓ Crank-Nicolson matches reference solution!");
+}
+
+#[test]
+fn test_adi_strided_vs_blocked() {
+    // 列スイープの走査順を変えても演算順序は列ごとに同じなので、結果は一致する
+    let strided = AdiConfig { column_sweep: ColumnSweep::Strided, ..Default::default() };
+    let blocked = AdiConfig { column_sweep: ColumnSweep::Blocked { width: 48 }, ..Default::default() };
+
+    let mut strided_a = Grid::new();
+    let mut strided_b = Grid::new();
+    adi_single(&mut strided_a, &mut strided_b, TEST_STEPS, &strided);
+
+    let mut blocked_a = Grid::new();
+    let mut blocked_b = Grid::new();
+    adi_single(&mut blocked_a, &mut blocked_b, TEST_STEPS, &blocked);
+
+    assert!(
+        grids_are_equal(&strided_a, &blocked_a),
+        "ADI strided and blocked column sweeps produce different results"
+    );
+
+    println!("✓ ADI Strided vs Blocked: Results match!");
+}
+
+#[test]
+fn test_adi_single_vs_parallel() {
+    let config = AdiConfig::default();
+
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    adi_single(&mut single_a, &mut single_b, TEST_STEPS, &config);
+
+    let mut rayon_a = Grid::new();
+    let mut rayon_b = Grid::new();
+    adi_rayon(&mut rayon_a, &mut rayon_b, TEST_STEPS, &config);
+
+    let mut band_a = Grid::new();
+    let mut band_b = Grid::new();
+    adi_band_unsafe(&mut band_a, &mut band_b, TEST_STEPS, &config, 3);
+
+    assert!(
+        grids_are_equal(&single_a, &rayon_a),
+        "ADI single-thread and Rayon implementations produce different results"
+    );
+    assert!(
+        grids_are_equal(&single_a, &band_a),
+        "ADI single-thread and band-threaded implementations produce different results"
+    );
+
+    println!("✓ ADI Single vs Rayon vs Band: Results match!");
+}
+
+#[test]
+fn test_adi_large_dt_matches_reference() {
+    // ADIは大きな時間刻みでも安定。各半ステップの三重対角方程式を Gauss–Seidel で解いた参照解と比較する
+    let dt = explicit_dt_limit() * 20.0;
+    let (hx, hy) = (0.5 * ALPHA * dt / (DX * DX), 0.5 * ALPHA * dt / (DY * DY));
+
+    let mut reference = Grid::new().data.to_vec();
+    for _ in 0..TEST_STEPS {
+        // 前半: (I - hy·Lj) u* = (I + hx·Li) u^n、後半: (I - hx·Li) u' = (I + hy·Lj) u*
+        let rhs = reference_explicit_part(&reference, hx, 0.0);
+        reference_implicit_solve(&mut reference, &rhs, 0.0, hy);
+        let rhs = reference_explicit_part(&reference, 0.0, hy);
+        reference_implicit_solve(&mut reference, &rhs, hx, 0.0);
+    }
+
+    let mut adi_a = Grid::new();
+    let mut adi_b = Grid::new();
+    adi_single(&mut adi_a, &mut adi_b, TEST_STEPS, &AdiConfig { dt, ..Default::default() });
+
+    assert_eq!(adi_a.data[N / 2 * M + M / 2], 100.0, "Heat source should remain 100.0");
+    let mut max_diff: f64 = 0.0;
+    for (x, y) in adi_a.data.iter().zip(reference.iter()) {
+        assert!(x.is_finite() && *x <= 100.0 + 1e-9, "Unstable value: {}", x);
+        max_diff = max_diff.max((x - y).abs());
+    }
+    assert!(max_diff < 1e-9, "ADI deviates from reference: {}", max_diff);
+
+    println!("✓ ADI stable with large dt and matches reference solution!");
+}
+
+#[test]
+fn test_rayon_vs_rk_forward_euler() {
+    // 前進オイラーのRunge–Kutta経路は rayon_parallel と同じ演算順序
+    let mut rayon_a = Grid::new();
+    let mut rayon_b = Grid::new();
+    rayon_parallel(&mut rayon_a, &mut rayon_b, TEST_STEPS);
+
+    let mut rk_a = Grid::new();
+    let mut rk_b = Grid::new();
+    let config = RungeKuttaConfig { integrator: Integrator::ForwardEuler, ..Default::default() };
+    runge_kutta(&mut rk_a, &mut rk_b, TEST_STEPS, &config);
+
+    assert!(
+        rayon_a.data.iter().zip(rk_a.data.iter()).all(|(x, y)| x == y),
+        "Forward Euler through the Runge-Kutta path is not bit-identical to rayon_parallel"
+    );
+
+    println!("✓ Rayon vs RK Forward Euler: Results match!");
+}
+
+#[test]
+fn test_rk_higher_order_agree() {
+    // 2次以上の積分法どうしは時間離散化誤差の範囲で一致する
+    let mut results = Vec::new();
+    for integrator in [Integrator::Heun, Integrator::SspRk3, Integrator::Rk4] {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        let config = RungeKuttaConfig { integrator, ..Default::default() };
+        runge_kutta(&mut a, &mut b, TEST_STEPS, &config);
+        assert_eq!(a.data[N / 2 * M + M / 2], 100.0, "Heat source should remain 100.0");
+        results.push(a);
+    }
+
+    for other in &results[..2] {
+        let max_diff = other.data.iter()
+            .zip(results[2].data.iter())
+            .fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+        assert!(max_diff < 0.1, "Higher-order integrators disagree: {}", max_diff);
+    }
+
+    println!("✓ Heun / SSP-RK3 / RK4: Results agree!");
+}
+
+#[test]
+fn test_rk4_stable_beyond_euler_limit() {
+    // RK4の安定領域は前進オイラーより広い
+    let dt = Integrator::ForwardEuler.dt_limit() * 1.3;
+    assert!(dt < Integrator::Rk4.dt_limit());
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    runge_kutta(&mut a, &mut b, 50, &RungeKuttaConfig { dt, integrator: Integrator::Rk4 });
+
+    for &v in a.data.iter() {
+        assert!(v.is_finite() && (-1e-9..=100.0 + 1e-9).contains(&v), "Unstable value: {}", v);
+    }
+
+    println!("✓ RK4 stable beyond forward Euler limit!");
+}
+
+#[test]
+fn test_uniform_diffusivity_matches_constant() {
+    // 全域で同じ係数なら調和平均は元の値に戻り、定数係数の実装と一致する
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step(&mut single_a, &mut single_b, TEST_STEPS);
+
+    let coef = Diffusivity::uniform(ALPHA);
+    let mut var_a = Grid::new();
+    let mut var_b = Grid::new();
+    jacobi_step_var(&mut var_a, &mut var_b, &coef, TEST_STEPS);
+
+    assert!(grids_are_equal(&single_a, &var_a), "Uniform diffusivity and constant ALPHA do not match");
+
+    println!("✓ Uniform diffusivity vs constant ALPHA: Results match!");
+}
+
+#[test]
+fn test_variable_diffusivity_all_implementations() {
+    let coef = Diffusivity::copper_trace();
+    assert!(coef.is_stable());
+
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step_var(&mut single_a, &mut single_b, &coef, TEST_STEPS);
+
+    let implementations: [(&str, VarImpl); 7] = [
+        ("Single Unsafe", jacobi_step_unsafe_var),
+        ("Unsafe Atomic Counter", unsafe_atomic_counter_var),
+        ("Safe Atomic Counter", atomic_counter_var),
+        ("Barrier", barrier_parallel_var),
+        ("Barrier Unsafe", barrier_unsafe_var),
+        ("Rayon", rayon_parallel_var),
+        ("Rayon Unsafe", rayon_unsafe_var),
+    ];
+
+    for (label, func) in implementations {
+        let mut a = Grid::new();
+        let mut b = Grid::new();
+        func(&mut a, &mut b, &coef, TEST_STEPS);
+        assert!(grids_are_equal(&single_a, &a), "{} (var) does not match single thread", label);
+    }
+
+    println!("✓ Variable diffusivity: all implementations match!");
+}
+
+#[test]
+fn test_copper_trace_conducts_along_trace() {
+    // 熱源から同じ距離なら、銅配線に沿った方向の方が基板側より温度が高い
+    let coef = Diffusivity::copper_trace();
+    assert!(coef.alpha[0] == FR4_ALPHA);
+
+    let mut a = Grid::new();
+    let mut b = Grid::new();
+    jacobi_step_var(&mut a, &mut b, &coef, 200);
+
+    let along = a.data[(N / 2) * M + M / 2 + 8];
+    let across = a.data[(N / 2 + 8) * M + M / 2];
+    assert!(along > across, "along={} across={}", along, across);
+
+    println!("✓ Copper trace conducts along the trace!");
+}
+
+#[test]
+fn test_overlap_schedules_match_single() {
+    // 内部行を先に計算しても、各セルの計算式と順序は同じなのでビット単位で一致する。待機は毎ステップ記録される
+
+    let implementations: [(&str, OverlapImpl); 2] = [
+        ("Safe Atomic Counter", atomic_counter_overlap),
+        ("Unsafe Atomic Counter", unsafe_atomic_counter_overlap),
+    ];
+
+    for (label, func) in implementations {
+        for schedule in HaloSchedule::ALL {
+            for steps in [1, TEST_STEPS, TEST_STEPS + 1] {
+                let mut single_a = Grid::new();
+                let mut single_b = Grid::new();
+                jacobi_step(&mut single_a, &mut single_b, steps);
+
+                let mut a = Grid::new();
+                let mut b = Grid::new();
+                let stats = func(&mut a, &mut b, steps, schedule);
+                assert_eq!(a.data.as_slice(), single_a.data.as_slice(), "{} ({}) steps={}", label, schedule.label(), steps);
+                assert!(stats.waits >= 2 * steps && stats.blocked <= stats.waits);
+            }
+        }
+    }
+
+    println!("✓ Overlapped halo exchange matches single thread!");
+}
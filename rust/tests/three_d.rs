@@ -0,0 +1,96 @@
+use jacobi_rust::grid3::{explicit_dt_limit3, Grid3, CENTER3, NX, NY, NZ, PLANE};
+use jacobi_rust::grid::DT;
+use jacobi_rust::implementations::safe::three_d::{atomic_counter3, barrier3_parallel, jacobi3_step, rayon3_parallel};
+use jacobi_rust::implementations::unsafe_impl::three_d_unsafe::{
+    atomic_counter3_unsafe, barrier3_unsafe, jacobi3_step_unsafe, rayon3_unsafe,
+};
+
+const TEST_STEPS: usize = 10;
+const EPSILON: f64 = 1e-10;
+
+type Solver3 = fn(&mut Grid3, &mut Grid3, usize);
+type SlabSolver3 = fn(&mut Grid3, &mut Grid3, usize, usize);
+
+fn grids_are_equal(grid1: &Grid3, grid2: &Grid3) -> bool {
+    for i in 0..grid1.data.len() {
+        let diff = (grid1.data[i] - grid2.data[i]).abs();
+        if diff > EPSILON {
+            eprintln!("Mismatch at index {}: {} vs {} (diff: {})", i, grid1.data[i], grid2.data[i], diff);
+            return false;
+        }
+    }
+    true
+}
+
+fn reference() -> Grid3 {
+    let mut a = Grid3::new();
+    let mut b = Grid3::new();
+    jacobi3_step(&mut a, &mut b, TEST_STEPS);
+    a
+}
+
+#[test]
+fn test_3d_single_vs_others() {
+    let single = reference();
+
+    let implementations: [(&str, Solver3); 3] = [
+        ("Single Unsafe", jacobi3_step_unsafe),
+        ("Rayon", rayon3_parallel),
+        ("Rayon Unsafe", rayon3_unsafe),
+    ];
+    for (label, func) in implementations {
+        let mut a = Grid3::new();
+        let mut b = Grid3::new();
+        func(&mut a, &mut b, TEST_STEPS);
+        assert!(grids_are_equal(&single, &a), "3D {} does not match single thread", label);
+    }
+
+    println!("✓ 3D single / rayon: Results match!");
+}
+
+#[test]
+fn test_3d_slab_implementations() {
+    // スラブ数が平面数を割り切らない場合や、1スラブの場合も含める
+    let single = reference();
+
+    let implementations: [(&str, SlabSolver3); 4] = [
+        ("Barrier", barrier3_parallel),
+        ("Barrier Unsafe", barrier3_unsafe),
+        ("Safe Atomic Counter", atomic_counter3),
+        ("Unsafe Atomic Counter", atomic_counter3_unsafe),
+    ];
+    for (label, func) in implementations {
+        for threads in [1, 3, 4] {
+            let mut a = Grid3::new();
+            let mut b = Grid3::new();
+            func(&mut a, &mut b, TEST_STEPS, threads);
+            assert!(grids_are_equal(&single, &a), "3D {} with {} threads does not match", label, threads);
+        }
+    }
+
+    println!("✓ 3D slab implementations: Results match!");
+}
+
+#[test]
+fn test_3d_heat_source_and_boundaries() {
+    assert!(DT <= explicit_dt_limit3());
+
+    let grid = reference();
+    assert_eq!(grid.data[CENTER3], 100.0, "Heat source should remain 100.0");
+
+    // 外周の面は0のまま
+    for k in [0, NZ - 1] {
+        assert!(grid.data[k * PLANE..(k + 1) * PLANE].iter().all(|&v| v == 0.0));
+    }
+    for k in 0..NZ {
+        for j in 0..NY {
+            assert_eq!(grid.data[Grid3::index(0, j, k)], 0.0);
+            assert_eq!(grid.data[Grid3::index(NX - 1, j, k)], 0.0);
+        }
+    }
+
+    // 熱源から z 方向にも拡散している
+    assert!(grid.data[CENTER3 + PLANE] > 0.0 && grid.data[CENTER3 - PLANE] > 0.0);
+
+    println!("✓ 3D heat source and boundaries preserved!");
+}
@@ -0,0 +1,71 @@
+use jacobi_rust::grid::{Grid, N, M};
+use jacobi_rust::implementations::safe::rayon::rayon::rayon_tiled;
+use jacobi_rust::implementations::safe::single::{jacobi_step, jacobi_step_tiled};
+use jacobi_rust::implementations::unsafe_impl::rayon_unsafe::rayon_unsafe_tiled;
+use jacobi_rust::implementations::unsafe_impl::single_unsafe::jacobi_step_unsafe_tiled;
+use jacobi_rust::tiling::TileConfig;
+
+const TEST_STEPS: usize = 11;
+
+type TiledImpl = fn(&mut Grid, &mut Grid, usize, &TileConfig);
+
+#[test]
+fn test_tiled_bit_identical() {
+    // 計算順序が変わるだけなので、タイルの形によらず jacobi_step とビット単位で一致する
+    // （割り切れないタイル、格子より大きいタイル、1×1 タイルも含む）
+    let mut single_a = Grid::new();
+    let mut single_b = Grid::new();
+    jacobi_step(&mut single_a, &mut single_b, TEST_STEPS);
+
+    let implementations: [(&str, TiledImpl); 4] = [
+        ("Single Tiled", jacobi_step_tiled),
+        ("Single Unsafe Tiled", jacobi_step_unsafe_tiled),
+        ("Rayon Tiled", rayon_tiled),
+        ("Rayon Unsafe Tiled", rayon_unsafe_tiled),
+    ];
+    let configs = [
+        TileConfig::default(),
+        TileConfig::new(7, 5),
+        TileConfig::new(64, 32),
+        TileConfig::new(1, 1),
+        TileConfig::new(M * 2, N * 2),
+    ];
+
+    for tiles in &configs {
+        for (label, func) in implementations {
+            let mut a = Grid::new();
+            let mut b = Grid::new();
+            func(&mut a, &mut b, TEST_STEPS, tiles);
+            assert!(
+                single_a.data.iter().zip(a.data.iter()).all(|(x, y)| x == y),
+                "{} {} is not bit-identical to jacobi_step", label, tiles.label()
+            );
+        }
+    }
+
+    println!("✓ Tiled kernels: bit-identical to jacobi_step");
+}
+
+#[test]
+fn test_tiles_cover_interior_once() {
+    // タイルが内部領域をちょうど1回ずつ覆う
+    for tiles in [TileConfig::new(7, 5), TileConfig::new(0, 0), TileConfig::new(M, 3)] {
+        let mut count = vec![0u8; N * M];
+        for (rows, cols) in tiles.tiles() {
+            assert!(rows.len() <= tiles.height.max(1) && cols.len() <= tiles.width.max(1));
+            for i in rows {
+                for j in cols.clone() {
+                    count[i * M + j] += 1;
+                }
+            }
+        }
+        for i in 0..N {
+            for j in 0..M {
+                let interior = i > 0 && i < N - 1 && j > 0 && j < M - 1;
+                assert_eq!(count[i * M + j], interior as u8, "{} at ({}, {})", tiles.label(), i, j);
+            }
+        }
+    }
+
+    println!("✓ Tiles cover the interior exactly once!");
+}
@@ -0,0 +1,91 @@
+use jacobi_rust::implementations::safe::runge_kutta::Integrator;
+use jacobi_rust::validation::{
+    error_norms, observed_orders, run_case, run_case_rect, spatial_convergence, temporal_convergence, AnalyticCase, Reference,
+};
+
+const SINE: AnalyticCase = AnalyticCase::SineMode { kx: 1, ky: 1 };
+const GAUSSIAN: AnalyticCase = AnalyticCase::Gaussian { amplitude: 1.0, sigma0: 0.05 };
+
+#[test]
+fn test_error_norms() {
+    // 3×3 の内部点は中心の1点だけ
+    let numerical = [0.0, 0.0, 0.0, 0.0, 1.5, 0.0, 0.0, 0.0, 0.0];
+    let exact = [9.0, 9.0, 9.0, 9.0, 1.0, 9.0, 9.0, 9.0, 9.0];
+    let norms = error_norms(&numerical, &exact, 3, 3);
+
+    assert_eq!(norms.l1, 0.5);
+    assert_eq!(norms.l2, 0.5);
+    assert_eq!(norms.linf, 0.5);
+
+    println!("✓ Error norms: boundary excluded, values correct!");
+}
+
+#[test]
+fn test_spatial_convergence_sine_mode() {
+    let runs = spatial_convergence(&SINE, &[17, 33, 65], 0.1, 0.05, Integrator::Rk4);
+    let orders = observed_orders(&runs, |r| r.dx);
+
+    for order in &orders {
+        assert!((order - 2.0).abs() < 0.15, "Spatial order should be 2, got {:?}", orders);
+    }
+    assert!(runs[2].norms.linf < 1e-4, "Error too large: {:?}", runs[2].norms);
+
+    println!("✓ Sine mode spatial convergence: orders {:?}", orders);
+}
+
+#[test]
+fn test_spatial_convergence_gaussian() {
+    let runs = spatial_convergence(&GAUSSIAN, &[33, 65], 0.1, 0.002, Integrator::Rk4);
+    let orders = observed_orders(&runs, |r| r.dx);
+
+    assert!((orders[0] - 2.0).abs() < 0.2, "Spatial order should be 2, got {:?}", orders);
+
+    println!("✓ Gaussian spatial convergence: orders {:?}", orders);
+}
+
+#[test]
+fn test_temporal_convergence_orders() {
+    for integrator in Integrator::ALL {
+        let runs = temporal_convergence(&SINE, 17, &[1e-3, 5e-4, 2.5e-4], 0.05, integrator);
+        let orders = observed_orders(&runs, |r| r.dt);
+        let expected = integrator.order() as f64;
+
+        for order in &orders {
+            assert!(
+                (order - expected).abs() < 0.15,
+                "{}: temporal order should be {}, got {:?}",
+                integrator.label(), expected, orders
+            );
+        }
+
+        println!("✓ {} temporal convergence: orders {:?}", integrator.label(), orders);
+    }
+}
+
+#[test]
+fn test_semi_discrete_reference_isolates_time_error() {
+    // 空間離散化後の厳密解と比べれば、RK4の誤差は丸め誤差レベルまで小さくなる
+    let run = run_case(&SINE, 17, 2.5e-4, 0.05, Integrator::Rk4, Reference::SemiDiscrete);
+    assert!(run.norms.linf < 1e-11, "Unexpected error: {:?}", run.norms);
+
+    // 連続問題の厳密解と比べると空間誤差が残る
+    let run = run_case(&SINE, 17, 2.5e-4, 0.05, Integrator::Rk4, Reference::Exact);
+    assert!(run.norms.linf > 1e-4, "Spatial error should dominate: {:?}", run.norms);
+
+    println!("✓ Semi-discrete reference isolates temporal error!");
+}
+
+#[test]
+fn test_anisotropic_spacing() {
+    // Δx = 1/32, Δy = 1/64 の長方形セル。方向ごとの重みが正しければ
+    // 時間誤差だけが残り、空間誤差も粗い方向の Δx² 程度に収まる
+    let case = AnalyticCase::SineMode { kx: 1, ky: 2 };
+    let run = run_case_rect(&case, 33, 65, 1e-5, 0.02, Integrator::Rk4, Reference::SemiDiscrete);
+    assert!(run.dy < run.dx);
+    assert!(run.norms.linf < 1e-10, "Time error too large: {:?}", run.norms);
+
+    let run = run_case_rect(&case, 33, 65, 1e-5, 0.02, Integrator::Rk4, Reference::Exact);
+    assert!(run.norms.linf < 1e-3, "Spatial error too large: {:?}", run.norms);
+
+    println!("✓ Anisotropic spacing: {:?}", run.norms);
+}
@@ -24,9 +24,15 @@ echo ""
 RUST_RESULTS="$RESULTS_DIR/rust_results.txt"
 C_RESULTS="$RESULTS_DIR/c_results.txt"
 
-echo "GridSize,Implementation,Min,Median,Avg,Max" > "$RUST_RESULTS"
+echo "GridSize,Implementation,Min,Avg,Max" > "$RUST_RESULTS"
 echo "GridSize,Implementation,Min,Median,Avg,Max" > "$C_RESULTS"
 
+# grid.rs は定数行だけを書き換え、終了時（途中で失敗した場合も含む）に元の内容へ戻す
+GRID_RS="$PROJECT_ROOT/rust/src/grid.rs"
+GRID_RS_ORIG="$(mktemp)"
+cp "$GRID_RS" "$GRID_RS_ORIG"
+trap 'cp "$GRID_RS_ORIG" "$GRID_RS"; rm -f "$GRID_RS_ORIG" "$GRID_RS.bak"' EXIT
+
 # 各グリッドサイズで測定
 for SIZE in "${GRID_SIZES[@]}"; do
     echo "========================================="
@@ -34,96 +40,9 @@ for SIZE in "${GRID_SIZES[@]}"; do
     echo "========================================="
 
     # Rustのgrid.rsを更新
-    cat > "$PROJECT_ROOT/rust/src/grid.rs" << EOF
-pub const N: usize = ${SIZE};  // x方向セル数
-pub const M: usize = ${SIZE};  // y方向セル数
-pub const TIME_STEPS: usize = 100;  //ステップ数
-pub const WARMUP_STEPS: usize = 10; //ウォームアップ数
-pub const DT: f64 = 0.1;  //時間刻み幅
-pub const DX: f64 = 1.0;  //グリッドの1セルの「物理的距離」
-pub const ALPHA: f64 = 0.8;  // 拡散係数
-
-
-#[derive(Clone,Debug)]
-pub struct Grid {
-    pub data: Vec<f64>,
-}
-
-impl Default for Grid {
-    fn default() -> Self {
-        Grid {
-            data: vec![0.0; N * M],
-        }
-    }
-}
-
-impl Grid {
-    pub fn new() -> Self {
-        let mut grid = Grid::default();
-        // 格子の中心に熱源を設定
-        grid.data[N / 2 * M + M / 2] = 100.0;
-        grid
-    }
-
-    // 格子の温度を表示
-    pub fn print(&self) {
-        for i in 0..N {
-            for j in 0..M {
-                print!("{:6.2} ", self.data[i * M + j]);
-            }
-            println!();
-        }
-    }
-
-    // バイナリ形式でファイルに保存
-    pub fn save_to_file(&self, path: &str) -> std::io::Result<()> {
-        use std::io::Write;
-        let mut file = std::fs::File::create(path)?;
-
-        // ヘッダー: N, M (4バイトずつ)
-        file.write_all(&(N as u32).to_le_bytes())?;
-        file.write_all(&(M as u32).to_le_bytes())?;
-
-        // データ: f64配列
-        for &val in &self.data {
-            file.write_all(&val.to_le_bytes())?;
-        }
-        Ok(())
-    }
-
-    // バイナリファイルから読み込み
-    pub fn load_from_file(path: &str) -> std::io::Result<Self> {
-        use std::io::Read;
-        let mut file = std::fs::File::open(path)?;
-
-        // ヘッダー読み込み
-        let mut buf_n = [0u8; 4];
-        let mut buf_m = [0u8; 4];
-        file.read_exact(&mut buf_n)?;
-        file.read_exact(&mut buf_m)?;
-
-        let n = u32::from_le_bytes(buf_n) as usize;
-        let m = u32::from_le_bytes(buf_m) as usize;
-
-        if n != N || m != M {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Grid size mismatch: expected {}x{}, got {}x{}", N, M, n, m)
-            ));
-        }
-
-        // データ読み込み
-        let mut data = Vec::with_capacity(N * M);
-        let mut buf = [0u8; 8];
-        for _ in 0..N * M {
-            file.read_exact(&mut buf)?;
-            data.push(f64::from_le_bytes(buf));
-        }
-
-        Ok(Grid { data })
-    }
-}
-EOF
+    sed -i.bak "s/pub const N: usize = [0-9]*;/pub const N: usize = ${SIZE};/" "$GRID_RS"
+    sed -i.bak "s/pub const M: usize = [0-9]*;/pub const M: usize = ${SIZE};/" "$GRID_RS"
+    sed -i.bak "s/pub const TIME_STEPS: usize = [0-9]*;/pub const TIME_STEPS: usize = 100;/" "$GRID_RS"
 
     # C言語のjacobi_common.hを更新
     sed -i.bak "s/#define N [0-9]*/#define N ${SIZE}/" "$PROJECT_ROOT/c/common/jacobi_common.h"
@@ -138,13 +57,10 @@ EOF
     echo "$OUTPUT"
     cd "$SCRIPTS_DIR"
 
-    # Rustの結果を抽出してCSVに保存
-    echo "$OUTPUT" | grep -A 5 "Single Thread:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",SingleThread,"$2}'  >> "$RUST_RESULTS" || true
-    echo "$OUTPUT" | grep -A 5 "Safe Semaphore:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",SafeSemaphore,"$2}' >> "$RUST_RESULTS" || true
-    echo "$OUTPUT" | grep -A 5 "Barrier:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",Barrier,"$2}' >> "$RUST_RESULTS" || true
-    echo "$OUTPUT" | grep -A 5 "Barrier Unsafe:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",BarrierUnsafe,"$2}' >> "$RUST_RESULTS" || true
-    echo "$OUTPUT" | grep -A 5 "Rayon:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",Rayon,"$2}' >> "$RUST_RESULTS" || true
-    echo "$OUTPUT" | grep -A 5 "Rayon Unsafe:" | grep "平均値:" | awk -v size="${SIZE}" '{gsub(/ms/,""); print size",RayonUnsafe,"$2}' >> "$RUST_RESULTS" || true
+    # Rustの結果を抽出してCSVに保存（"ラベル: min=..., avg=..., max=..." の行）
+    # タイル幅別のエントリ（Tiled 64x32 など）も含まれるため、格子サイズごとのタイリングの効果を比較できる
+    echo "$OUTPUT" | grep -E ": min=[0-9.]+, avg=[0-9.]+, max=[0-9.]+$" | \
+        sed -E "s/^(.*): min=([0-9.]+), avg=([0-9.]+), max=([0-9.]+)$/${SIZE},\1,\2,\3,\4/" >> "$RUST_RESULTS" || true
 
     echo ""
     echo "--- Cベンチマーク実行 ---"
@@ -171,100 +87,9 @@ echo "  Rust: $RUST_RESULTS"
 echo "  C:    $C_RESULTS"
 echo "========================================="
 
-# 元の設定に戻す (2048x2048, TIME_STEPS=1000)
-cat > rust/src/grid.rs << 'EOF'
-pub const N: usize = 2048;  // x方向セル数
-pub const M: usize = 2048;  // y方向セル数
-pub const TIME_STEPS: usize = 1000;  //ステップ数
-pub const WARMUP_STEPS: usize = 10; //ウォームアップ数
-pub const DT: f64 = 0.1;  //時間刻み幅
-pub const DX: f64 = 1.0;  //グリッドの1セルの「物理的距離」
-pub const ALPHA: f64 = 0.8;  // 拡散係数
-
-
-#[derive(Clone,Debug)]
-pub struct Grid {
-    pub data: Vec<f64>,
-}
-
-impl Default for Grid {
-    fn default() -> Self {
-        Grid {
-            data: vec![0.0; N * M],
-        }
-    }
-}
-
-impl Grid {
-    pub fn new() -> Self {
-        let mut grid = Grid::default();
-        // 格子の中心に熱源を設定
-        grid.data[N / 2 * M + M / 2] = 100.0;
-        grid
-    }
-
-    // 格子の温度を表示
-    pub fn print(&self) {
-        for i in 0..N {
-            for j in 0..M {
-                print!("{:6.2} ", self.data[i * M + j]);
-            }
-            println!();
-        }
-    }
-
-    // バイナリ形式でファイルに保存
-    pub fn save_to_file(&self, path: &str) -> std::io::Result<()> {
-        use std::io::Write;
-        let mut file = std::fs::File::create(path)?;
-
-        // ヘッダー: N, M (4バイトずつ)
-        file.write_all(&(N as u32).to_le_bytes())?;
-        file.write_all(&(M as u32).to_le_bytes())?;
-
-        // データ: f64配列
-        for &val in &self.data {
-            file.write_all(&val.to_le_bytes())?;
-        }
-        Ok(())
-    }
-
-    // バイナリファイルから読み込み
-    pub fn load_from_file(path: &str) -> std::io::Result<Self> {
-        use std::io::Read;
-        let mut file = std::fs::File::open(path)?;
-
-        // ヘッダー読み込み
-        let mut buf_n = [0u8; 4];
-        let mut buf_m = [0u8; 4];
-        file.read_exact(&mut buf_n)?;
-        file.read_exact(&mut buf_m)?;
-
-        let n = u32::from_le_bytes(buf_n) as usize;
-        let m = u32::from_le_bytes(buf_m) as usize;
-
-        if n != N || m != M {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Grid size mismatch: expected {}x{}, got {}x{}", N, M, n, m)
-            ));
-        }
-
-        // データ読み込み
-        let mut data = Vec::with_capacity(N * M);
-        let mut buf = [0u8; 8];
-        for _ in 0..N * M {
-            file.read_exact(&mut buf)?;
-            data.push(f64::from_le_bytes(buf));
-        }
-
-        Ok(Grid { data })
-    }
-}
-EOF
-
+# 元の設定に戻す（Rust の grid.rs は trap で実行前の内容に戻す）
 sed -i.bak "s/#define N [0-9]*/#define N 2048/" c/common/jacobi_common.h
 sed -i.bak "s/#define M [0-9]*/#define M 2048/" c/common/jacobi_common.h
 sed -i.bak "s/#define TIME_STEPS [0-9]*/#define TIME_STEPS 1000/" c/common/jacobi_common.h
 
-echo "設定を元に戻しました (2048x2048, TIME_STEPS=1000)"
+echo "設定を元に戻しました (C: 2048x2048, TIME_STEPS=1000)"